@@ -0,0 +1,751 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::BitAnd;
+use std::ops::BitOr;
+use std::ops::BitXor;
+use std::ops::Not;
+use std::ops::Sub;
+use std::str::FromStr;
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::env;
+use crate::env::Statements;
+use crate::util;
+use crate::CompiledSieve;
+use crate::Residual;
+use crate::Sieve;
+use crate::SieveError;
+use crate::SieveNode;
+use crate::Span;
+
+/// A Residual class with a modulus and shift of arbitrary precision. A modulus of zero defines the null Residual, which contains no integers.
+///
+/// ```
+/// use num_bigint::BigInt;
+/// let r: xensieve::BigResidual = "100000000000000000000000000000000000000000@7".parse().unwrap();
+/// assert_eq!(r.contains(&BigInt::from(7)), true);
+/// assert_eq!(r.contains(&BigInt::from(8)), false);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BigResidual {
+    modulus: BigUint,
+    shift: BigUint,
+}
+
+impl BigResidual {
+    /// Construct a BigResidual, normalizing the shift to be less than the modulus.
+    pub fn new(modulus: BigUint, shift: BigUint) -> Self {
+        if modulus.is_zero() {
+            return Self {
+                modulus,
+                shift: BigUint::zero(),
+            };
+        }
+        let shift = shift % &modulus;
+        Self { modulus, shift }
+    }
+
+    /// Return the modulus.
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Return the shift, always less than the modulus.
+    pub fn shift(&self) -> &BigUint {
+        &self.shift
+    }
+
+    /// Return `true` if the value is contained with this BigResidual.
+    pub fn contains(&self, value: &BigInt) -> bool {
+        if self.modulus.is_zero() {
+            return false;
+        }
+        let m = BigInt::from(self.modulus.clone());
+        value.mod_floor(&m) == BigInt::from(self.shift.clone())
+    }
+
+    /// Return the BigResidual of values contained in both BigResiduals, or `None` if they have no values in common.
+    ///
+    /// ```
+    /// let r1: xensieve::BigResidual = "18446744073709551615@3".parse().unwrap();
+    /// let r2: xensieve::BigResidual = "18446744073709551614@5".parse().unwrap();
+    /// let r3 = r1.intersection(&r2).unwrap();
+    /// assert_eq!(r3.modulus(), &(r1.modulus() * r2.modulus()));
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let (m, s) =
+            util::big_intersection(&self.modulus, &other.modulus, &self.shift, &other.shift);
+        if m.is_zero() {
+            return None;
+        }
+        Some(Self::new(m, s))
+    }
+}
+
+impl fmt::Display for BigResidual {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.modulus, self.shift)
+    }
+}
+
+impl From<Residual> for BigResidual {
+    fn from(residual: Residual) -> Self {
+        Self::new(residual.modulus().into(), residual.shift().into())
+    }
+}
+
+impl TryFrom<&BigResidual> for Residual {
+    type Error = SieveError;
+
+    /// Convert to a `Residual`, returning a `SieveError::Overflow` if the modulus exceeds `u64`.
+    fn try_from(residual: &BigResidual) -> Result<Self, Self::Error> {
+        let m = residual.modulus.to_u64().ok_or(SieveError::Overflow)?;
+        // the shift is less than the modulus
+        let s = residual.shift.to_u64().ok_or(SieveError::Overflow)?;
+        Ok(Residual::new(m, s))
+    }
+}
+
+/// Parse a BigResidual from an operand in the form `M@S`, with the span used for error reporting.
+fn operand_to_residual(value: &str, span: Span) -> Result<BigResidual, SieveError> {
+    let malformed = || SieveError::MalformedResidual {
+        residual: value.to_string(),
+        span,
+    };
+    let parts: Vec<&str> = value.split('@').collect();
+    if parts.len() != 2 {
+        return Err(malformed());
+    }
+    let m = BigUint::from_str(parts[0]).map_err(|_e| malformed())?;
+    // the shift may be negative, and is normalized to be less than the modulus
+    let s = BigInt::from_str(parts[1]).map_err(|_e| malformed())?;
+    if m.is_zero() {
+        return Ok(BigResidual::new(m, BigUint::zero()));
+    }
+    let s = s.mod_floor(&BigInt::from(m.clone())).into_parts().1;
+    Ok(BigResidual::new(m, s))
+}
+
+impl FromStr for BigResidual {
+    type Err = SieveError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        operand_to_residual(value, Span::new(0, value.len()))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+impl SieveNode<BigResidual> {
+    /// Return the least common multiple of the non-zero moduli of all Residuals in this node, or 1 if there are none.
+    fn period(&self) -> BigUint {
+        match self {
+            SieveNode::Unit(residual) if residual.modulus.is_zero() => BigUint::one(),
+            SieveNode::Unit(residual) => residual.modulus.clone(),
+            SieveNode::Intersection(lhs, rhs)
+            | SieveNode::Union(lhs, rhs)
+            | SieveNode::SymmetricDifference(lhs, rhs)
+            | SieveNode::Difference(lhs, rhs) => lhs.period().lcm(&rhs.period()),
+            SieveNode::Inversion(part) => part.period(),
+        }
+    }
+
+    fn contains(&self, value: &BigInt) -> bool {
+        match self {
+            SieveNode::Unit(residual) => residual.contains(value),
+            SieveNode::Intersection(lhs, rhs) => lhs.contains(value) && rhs.contains(value),
+            SieveNode::Union(lhs, rhs) => lhs.contains(value) || rhs.contains(value),
+            SieveNode::SymmetricDifference(lhs, rhs) => lhs.contains(value) ^ rhs.contains(value),
+            SieveNode::Difference(lhs, rhs) => lhs.contains(value) && !rhs.contains(value),
+            SieveNode::Inversion(part) => !part.contains(value),
+        }
+    }
+}
+
+/// Return the disjoint BigResiduals whose union is the BigResidual `r` less its sub-BigResidual `i`, refining the modulus of `r` by one prime factor of the ratio of moduli at a time. Returns `None` if more than `limit` BigResiduals are required.
+fn split(r: &BigResidual, i: &BigResidual, limit: usize) -> Option<Vec<BigResidual>> {
+    let mut post: Vec<BigResidual> = Vec::new();
+    let mut current = r.clone();
+    let target = BigInt::from(i.shift.clone());
+    let mut q = &i.modulus / &r.modulus;
+    let mut p = BigUint::from(2u8);
+    while !q.is_one() {
+        // find the smallest prime factor of `q`; a factor greater than `limit` requires too many BigResiduals
+        while !(&q % &p).is_zero() {
+            if p > BigUint::from(limit) {
+                return None;
+            }
+            if &p * &p > q {
+                p = q.clone();
+                break;
+            }
+            p += 1u8;
+        }
+        let count = p.to_usize()?;
+        if post.len() + count - 1 > limit {
+            return None;
+        }
+        q /= &p;
+        let m = &current.modulus * &p;
+        let mut next = current.clone();
+        for k in 0..count {
+            let c = BigResidual::new(m.clone(), &current.shift + &current.modulus * k);
+            if c.contains(&target) {
+                next = c;
+            } else {
+                post.push(c);
+            }
+        }
+        current = next;
+    }
+    Some(post)
+}
+
+/// Return the disjoint BigResiduals whose union contains the values of the disjoint BigResiduals `lhs` that are not contained in any of `rhs`. Returns `None` if more than `limit` BigResiduals, or more than `limit` intersections, are required.
+fn difference(
+    lhs: Vec<BigResidual>,
+    rhs: &[BigResidual],
+    limit: usize,
+) -> Option<Vec<BigResidual>> {
+    let mut post = lhs;
+    let mut steps: usize = 0;
+    for s in rhs {
+        steps += post.len();
+        if steps > limit {
+            return None;
+        }
+        let mut next: Vec<BigResidual> = Vec::new();
+        for r in post {
+            match r.intersection(s) {
+                None => next.push(r),
+                Some(i) if i == r => {}
+                Some(i) => next.extend(split(&r, &i, limit)?),
+            }
+            if next.len() > limit {
+                return None;
+            }
+        }
+        post = next;
+    }
+    Some(post)
+}
+
+/// Return disjoint BigResiduals whose union is equivalent to the node, or `None` if more than `limit` BigResiduals are required. As with the normal form of a `Sieve`, intersections are rewritten with `BigResidual::intersection`, and unions, differences, and inversions are made disjoint by removing the intersection from each BigResidual.
+fn to_disjoint(node: &SieveNode<BigResidual>, limit: usize) -> Option<Vec<BigResidual>> {
+    let post = match node {
+        SieveNode::Unit(residual) if residual.modulus.is_zero() => Vec::new(),
+        SieveNode::Unit(residual) => vec![residual.clone()],
+        SieveNode::Intersection(lhs, rhs) => {
+            let (l, r) = (to_disjoint(lhs, limit)?, to_disjoint(rhs, limit)?);
+            if l.len().saturating_mul(r.len()) > limit {
+                return None;
+            }
+            // the intersections of disjoint BigResiduals are disjoint
+            let mut post: Vec<BigResidual> = Vec::new();
+            for a in l.iter() {
+                post.extend(r.iter().filter_map(|b| a.intersection(b)));
+                if post.len() > limit {
+                    return None;
+                }
+            }
+            post
+        }
+        SieveNode::Union(lhs, rhs) => {
+            let mut l = to_disjoint(lhs, limit)?;
+            let d = difference(to_disjoint(rhs, limit)?, &l, limit)?;
+            l.extend(d);
+            l
+        }
+        SieveNode::SymmetricDifference(lhs, rhs) => {
+            let (l, r) = (to_disjoint(lhs, limit)?, to_disjoint(rhs, limit)?);
+            let mut a = difference(l.clone(), &r, limit)?;
+            a.extend(difference(r, &l, limit)?);
+            a
+        }
+        SieveNode::Difference(lhs, rhs) => {
+            difference(to_disjoint(lhs, limit)?, &to_disjoint(rhs, limit)?, limit)?
+        }
+        SieveNode::Inversion(part) => difference(
+            vec![BigResidual::new(BigUint::one(), BigUint::zero())],
+            &to_disjoint(part, limit)?,
+            limit,
+        )?,
+    };
+    Some(post).filter(|p| p.len() <= limit)
+}
+
+/// A Xenakis Sieve of Residuals with moduli and shifts of arbitrary precision, supporting membership tests and iteration of values beyond `i128`. Available with the `bigint` feature.
+///
+/// ```
+/// use num_bigint::BigInt;
+/// let s = xensieve::BigSieve::new("(340282366920938463463374607431768211457@1 | 4@2) & !8@2");
+/// assert_eq!(s.contains(&BigInt::from(1)), true);
+/// assert_eq!(s.contains(&BigInt::from(6)), true);
+/// assert_eq!(s.period().to_string(), "2722258935367507707706996859454145691656");
+/// ```
+#[derive(Clone, Debug)]
+pub struct BigSieve {
+    root: SieveNode<BigResidual>,
+}
+
+impl BitAnd for BigSieve {
+    type Output = BigSieve;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        BigSieve {
+            root: SieveNode::Intersection(Box::new(self.root), Box::new(rhs.root)),
+        }
+    }
+}
+
+impl BitOr for BigSieve {
+    type Output = BigSieve;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        BigSieve {
+            root: SieveNode::Union(Box::new(self.root), Box::new(rhs.root)),
+        }
+    }
+}
+
+impl BitXor for BigSieve {
+    type Output = BigSieve;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        BigSieve {
+            root: SieveNode::SymmetricDifference(Box::new(self.root), Box::new(rhs.root)),
+        }
+    }
+}
+
+impl Sub for BigSieve {
+    type Output = BigSieve;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        BigSieve {
+            root: SieveNode::Difference(Box::new(self.root), Box::new(rhs.root)),
+        }
+    }
+}
+
+impl Not for BigSieve {
+    type Output = BigSieve;
+
+    fn not(self) -> Self::Output {
+        BigSieve {
+            root: SieveNode::Inversion(Box::new(self.root)),
+        }
+    }
+}
+
+impl fmt::Display for BigSieve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sieve{{{}}}", self.root)
+    }
+}
+
+impl FromStr for BigSieve {
+    type Err = SieveError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_new(value)
+    }
+}
+
+impl From<BigResidual> for BigSieve {
+    fn from(residual: BigResidual) -> Self {
+        Self {
+            root: SieveNode::Unit(residual),
+        }
+    }
+}
+
+impl From<&Sieve> for BigSieve {
+    fn from(sieve: &Sieve) -> Self {
+        let root = sieve
+            .root
+            .try_map(&|r: &Residual| Ok(BigResidual::from(*r)))
+            .unwrap();
+        Self { root }
+    }
+}
+
+impl From<Sieve> for BigSieve {
+    fn from(sieve: Sieve) -> Self {
+        Self::from(&sieve)
+    }
+}
+
+impl TryFrom<&BigSieve> for Sieve {
+    type Error = SieveError;
+
+    /// Convert to a `Sieve`, returning a `SieveError::Overflow` if any modulus exceeds `u64`.
+    fn try_from(sieve: &BigSieve) -> Result<Self, Self::Error> {
+        let root = sieve
+            .root
+            .try_map(&|r: &BigResidual| Residual::try_from(r))?;
+        Ok(Self { root })
+    }
+}
+
+impl Statements for BigSieve {
+    type Residual = BigResidual;
+
+    fn residual(operand: &str, span: Span) -> Result<BigResidual, SieveError> {
+        operand_to_residual(operand, span)
+    }
+
+    fn root(&self) -> &SieveNode<BigResidual> {
+        &self.root
+    }
+
+    fn from_root(root: SieveNode<BigResidual>) -> Self {
+        Self { root }
+    }
+}
+
+impl BigSieve {
+    /// Construct a BigSieve from a string expression, with the same grammar as `Sieve::new`. Panics if the expression is invalid.
+    pub fn new(value: &str) -> Self {
+        match Self::try_new(value) {
+            Ok(s) => s,
+            Err(e) => panic!("Invalid syntax: {e}"),
+        }
+    }
+
+    /// Construct a BigSieve from a string expression, with the same grammar as `Sieve::try_new`, returning a `SieveError` if the expression is invalid. The expression may bind names to sub-expressions with statements separated by `;`.
+    ///
+    /// ```
+    /// use num_bigint::BigInt;
+    /// let s = xensieve::BigSieve::try_new("a = 18446744073709551616@1; a | 3@0 - a").unwrap();
+    /// assert_eq!(s.contains(&BigInt::from(3)), true);
+    /// assert!(xensieve::BigSieve::try_new("a = 3@0; b").is_err());
+    /// ```
+    pub fn try_new(value: &str) -> Result<Self, SieveError> {
+        match env::eval_statements(&mut HashMap::new(), value)? {
+            Some(s) => Ok(s),
+            // the last statement is a binding
+            None => Err(SieveError::EmptyExpression {
+                span: Span::new(value.len(), 0),
+            }),
+        }
+    }
+
+    /// Return `true` if the value is contained with this BigSieve.
+    pub fn contains(&self, value: &BigInt) -> bool {
+        self.root.contains(value)
+    }
+
+    /// Return the period of this BigSieve, the least common multiple of all non-zero moduli. As the result is of arbitrary precision, it cannot overflow.
+    pub fn period(&self) -> BigUint {
+        self.root.period()
+    }
+
+    /// Return the sorted disjoint BigResiduals of this BigSieve, or a `SieveError::ExceedsBudget` if more are required than permitted by `CompiledSieve::DEFAULT_BUDGET`; as the period might exceed `u64`, the period of the error saturates at `u64::MAX`.
+    fn disjoint_residuals(&self) -> Result<Vec<BigResidual>, SieveError> {
+        let budget = CompiledSieve::DEFAULT_BUDGET;
+        let limit = budget / std::mem::size_of::<Residual>();
+        let mut post = to_disjoint(&self.root, limit).ok_or_else(|| SieveError::ExceedsBudget {
+            period: u64::try_from(self.period()).unwrap_or(u64::MAX),
+            budget,
+        })?;
+        post.sort();
+        Ok(post)
+    }
+
+    /// Return an equivalent BigSieve in normal form: a union of disjoint Residuals. Where all moduli fit `u64`, this is the normal form of the equivalent `Sieve`; otherwise, the tree is rewritten with `BigResidual::intersection`, as for `Sieve::to_normal_form`. A `SieveError::ExceedsBudget` is returned if the disjoint BigResiduals exceed the budget of `Sieve::compile`.
+    ///
+    /// ```
+    /// let s = xensieve::BigSieve::new("18446744073709551615@1 & 18446744073709551614@1 | 2@0");
+    /// assert_eq!(
+    ///     s.to_normal_form().unwrap().to_string(),
+    ///     "Sieve{2@0|340282366920938463408034375210639556610@1}"
+    /// );
+    /// ```
+    pub fn to_normal_form(&self) -> Result<Self, SieveError> {
+        match Sieve::try_from(self).and_then(|s| s.to_normal_form()) {
+            Ok(s) => Ok(s.into()),
+            Err(_) => Ok(Self {
+                root: SieveNode::from_residuals(
+                    self.disjoint_residuals()?,
+                    BigResidual::new(BigUint::zero(), BigUint::zero()),
+                ),
+            }),
+        }
+    }
+
+    /// For the iterator provided as an input, iterate the subset of values contained within this BigSieve.
+    pub fn iter_value<'a, I>(&'a self, iterator: I) -> impl Iterator<Item = BigInt> + 'a
+    where
+        I: IntoIterator<Item = BigInt>,
+        I::IntoIter: 'a,
+    {
+        iterator.into_iter().filter(move |v| self.contains(v))
+    }
+
+    /// Iterate, in ascending order, all values of the BigSieve greater than or equal to `start`, including values beyond `i128`. Values are found directly from the members of one period or, where the period cannot be compiled, by stepping each of the disjoint BigResiduals of the normal form. A `SieveError::ExceedsBudget` is returned if the disjoint BigResiduals exceed the budget of `Sieve::compile`.
+    ///
+    /// ```
+    /// use num_bigint::BigInt;
+    /// let s = xensieve::BigSieve::new("3@0|4@1");
+    /// let start: BigInt = BigInt::from(i128::MAX) * 1000;
+    /// let post: Vec<BigInt> = s.iter_from(start.clone()).unwrap().take(3).collect();
+    /// assert!(post.iter().all(|v| v >= &start && s.contains(v)));
+    /// ```
+    pub fn iter_from(&self, start: BigInt) -> Result<IterBigMembers, SieveError> {
+        match Sieve::try_from(self).and_then(|s| s.compile()) {
+            Ok(c) => {
+                let (period, offsets) = c.into_offsets();
+                Ok(IterBigMembers::from_offsets(period, offsets, start))
+            }
+            Err(_) => Ok(IterBigMembers::from_residuals(
+                self.disjoint_residuals()?,
+                start,
+            )),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The position of an `IterBigMembers`: for a lookup table, the start of the current period and the index of the next offset; for disjoint BigResiduals, the next value of each BigResidual, ordered such that the smallest is first.
+#[derive(Clone, Debug)]
+enum BigPosition {
+    Table {
+        period: BigInt,
+        offsets: Vec<u64>,
+        base: BigInt,
+        index: usize,
+    },
+    Residuals {
+        moduli: Vec<BigInt>,
+        heap: BinaryHeap<Reverse<(BigInt, usize)>>,
+    },
+}
+
+/// An iterator of the members of a BigSieve, in ascending order and without bound, from a start value of arbitrary precision.
+#[derive(Clone, Debug)]
+pub struct IterBigMembers {
+    position: BigPosition,
+}
+
+impl IterBigMembers {
+    fn from_offsets(period: u64, offsets: Vec<u64>, start: BigInt) -> Self {
+        let period = BigInt::from(period);
+        let (q, r) = start.div_mod_floor(&period);
+        // the remainder is less than the period, which fits u64
+        let r = r.to_u64().unwrap();
+        let index = offsets.partition_point(|&o| o < r);
+        Self {
+            position: BigPosition::Table {
+                base: q * &period,
+                period,
+                offsets,
+                index,
+            },
+        }
+    }
+
+    fn from_residuals(residuals: Vec<BigResidual>, start: BigInt) -> Self {
+        let moduli: Vec<BigInt> = residuals
+            .iter()
+            .map(|r| BigInt::from(r.modulus.clone()))
+            .collect();
+        let heap = residuals
+            .iter()
+            .zip(moduli.iter())
+            .enumerate()
+            .map(|(i, (r, m))| {
+                let shift = BigInt::from(r.shift.clone());
+                let first = &start + (shift - &start).mod_floor(m);
+                Reverse((first, i))
+            })
+            .collect();
+        Self {
+            position: BigPosition::Residuals { moduli, heap },
+        }
+    }
+}
+
+impl Iterator for IterBigMembers {
+    type Item = BigInt;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.position {
+            BigPosition::Table {
+                period,
+                offsets,
+                base,
+                index,
+            } => {
+                if offsets.is_empty() {
+                    return None;
+                }
+                if *index == offsets.len() {
+                    *base += &*period;
+                    *index = 0;
+                }
+                let post = &*base + offsets[*index];
+                *index += 1;
+                Some(post)
+            }
+            BigPosition::Residuals { moduli, heap } => {
+                let Reverse((v, i)) = heap.pop()?;
+                // as the BigResiduals are disjoint, no value is produced twice
+                heap.push(Reverse((&v + &moduli[i], i)));
+                Some(v)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_residual_a() {
+        let r1 = BigResidual::new(BigUint::from(5u8), BigUint::from(12u8));
+        assert_eq!(r1.to_string(), "5@2");
+        assert!(r1.contains(&BigInt::from(-3)));
+        assert!(!r1.contains(&BigInt::from(3)));
+        let r2 = BigResidual::new(BigUint::zero(), BigUint::from(3u8));
+        assert_eq!(r2.to_string(), "0@0");
+        assert!(!r2.contains(&BigInt::zero()));
+    }
+
+    #[test]
+    fn test_big_residual_b() {
+        assert!("3@".parse::<BigResidual>().is_err());
+        assert!("-3@1".parse::<BigResidual>().is_err());
+        assert_eq!("7@-1".parse::<BigResidual>().unwrap().to_string(), "7@6");
+        let r1: BigResidual = "7@9".parse().unwrap();
+        assert_eq!(Residual::try_from(&r1).unwrap(), Residual::new(7, 2));
+        let r2: BigResidual = "18446744073709551616@1".parse().unwrap();
+        assert_eq!(Residual::try_from(&r2), Err(SieveError::Overflow));
+    }
+
+    #[test]
+    fn test_big_residual_intersection_a() {
+        let r1: BigResidual = "4@0".parse().unwrap();
+        assert_eq!(r1.intersection(&"6@1".parse().unwrap()), None);
+        assert_eq!(
+            r1.intersection(&"6@2".parse().unwrap()),
+            Some("12@8".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_big_sieve_a() {
+        let s1 = BigSieve::new("3@0|4@1&!(5@0^7@1)");
+        let s2 = Sieve::new("3@0|4@1&!(5@0^7@1)");
+        for v in -200..200 {
+            assert_eq!(s1.contains(&BigInt::from(v)), s2.contains(v));
+        }
+        assert_eq!(s1.to_string(), s2.to_string());
+        assert_eq!(s1.period(), BigUint::from(s2.period().unwrap()));
+    }
+
+    #[test]
+    fn test_big_sieve_b() {
+        let s1 = BigSieve::new("18446744073709551615@1 & 18446744073709551614@1");
+        assert_eq!(
+            s1.period(),
+            BigUint::from(u64::MAX) * BigUint::from(u64::MAX - 1)
+        );
+        assert!(s1.contains(&BigInt::from(1)));
+        let v = BigInt::from(s1.period()) * 3 + 1;
+        assert!(s1.contains(&v));
+        assert!(!s1.contains(&(v + 1)));
+        assert_eq!(
+            s1.to_normal_form().unwrap().to_string(),
+            "Sieve{340282366920938463408034375210639556610@1}"
+        );
+        let p = BigInt::from(s1.period());
+        let post: Vec<BigInt> = s1.iter_from(BigInt::from(2)).unwrap().take(2).collect();
+        assert_eq!(post, vec![&p + 1, &p * 2 + 1]);
+    }
+
+    #[test]
+    fn test_big_sieve_c() {
+        // the Residuals of the difference cannot be made disjoint within the budget
+        let s1 = BigSieve::new("18446744073709551615@1 - 18446744073709551557@1");
+        let e1 = SieveError::ExceedsBudget {
+            period: u64::MAX,
+            budget: CompiledSieve::DEFAULT_BUDGET,
+        };
+        assert_eq!(s1.to_normal_form().unwrap_err(), e1);
+        assert_eq!(s1.iter_from(BigInt::zero()).unwrap_err(), e1);
+    }
+
+    #[test]
+    fn test_big_sieve_try_new_a() {
+        let s1 = BigSieve::new("a = 18446744073709551616@1; b = 4@0; (a | b) - 8@0");
+        let s2 = BigSieve::new("(18446744073709551616@1 | 4@0) - 8@0");
+        assert_eq!(s1.to_string(), s2.to_string());
+        assert_eq!(BigSieve::new("Sieve{a = 3@1; a}").to_string(), "Sieve{3@1}");
+        assert_eq!(
+            BigSieve::try_new("a = 3@1; a | b").unwrap_err(),
+            SieveError::UndefinedName {
+                name: "b".to_string(),
+                span: Span::new(13, 1)
+            }
+        );
+        assert!(matches!(
+            BigSieve::try_new("a = 3@1"),
+            Err(SieveError::EmptyExpression { .. })
+        ));
+    }
+
+    #[test]
+    fn test_big_sieve_to_normal_form_a() {
+        let s1 = BigSieve::new("!(2@0) | 4@0");
+        assert_eq!(s1.to_normal_form().unwrap().to_string(), "Sieve{4@0|2@1}");
+    }
+
+    #[test]
+    fn test_big_sieve_iter_from_a() {
+        let s1 = BigSieve::new("5@1|3@0");
+        let start: BigInt = BigInt::from(i128::MIN) * 7;
+        let post: Vec<BigInt> = s1.iter_from(start.clone()).unwrap().take(20).collect();
+        let expected: Vec<BigInt> = (0..60)
+            .map(|i| &start + i)
+            .filter(|v| s1.contains(v))
+            .take(20)
+            .collect();
+        assert_eq!(post, expected);
+    }
+
+    #[test]
+    fn test_big_sieve_iter_from_b() {
+        let s1 = BigSieve::new("3@0&3@1");
+        assert_eq!(s1.iter_from(BigInt::zero()).unwrap().next(), None);
+    }
+
+    #[test]
+    fn test_big_sieve_iter_from_c() {
+        let s1 = BigSieve::new("18446744073709551616@3 | 18446744073709551616@1 - 3@1");
+        let post: Vec<BigInt> = s1.iter_from(BigInt::zero()).unwrap().take(4).collect();
+        let m = BigInt::from(u64::MAX) + 1;
+        assert_eq!(post, vec![BigInt::from(3), &m + 1, &m + 3, &m * 2 + 1]);
+        assert_eq!(
+            s1.to_normal_form().unwrap().to_string(),
+            "Sieve{18446744073709551616@3|55340232221128654848@18446744073709551617|55340232221128654848@36893488147419103233}"
+        );
+    }
+
+    #[test]
+    fn test_big_sieve_iter_value_a() {
+        let s1 = BigSieve::from(Sieve::new("4@1"));
+        let post: Vec<BigInt> = s1.iter_value((0..10).map(BigInt::from)).collect();
+        assert_eq!(
+            post,
+            vec![BigInt::from(1), BigInt::from(5), BigInt::from(9)]
+        );
+    }
+}
@@ -0,0 +1,713 @@
+use std::ops::Range;
+
+use crate::members::Members;
+use crate::members::SieveCursor;
+use crate::util::divisors;
+use crate::IterInterval;
+use crate::IterMembers;
+use crate::IterState;
+use crate::IterValue;
+use crate::Membership;
+use crate::PositionLast;
+use crate::Residual;
+use crate::SieveError;
+use crate::SieveInt;
+use crate::SieveNode;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Return the number of `u64` words needed to store `len` bits.
+#[inline(always)]
+fn word_count(len: usize) -> usize {
+    (len + WORD_BITS - 1) / WORD_BITS
+}
+
+/// Clear any bits beyond `len` in the last word, such that inverted words do not include values beyond the period.
+fn mask_tail(words: &mut [u64], len: usize) {
+    let rem = len % WORD_BITS;
+    if rem != 0 {
+        if let Some(last) = words.last_mut() {
+            *last &= (1u64 << rem) - 1;
+        }
+    }
+}
+
+/// Return the bits of the positions `0..len` contained in the node, evaluating operators word-wise rather than value by value.
+fn fill(node: &SieveNode, len: usize) -> Vec<u64> {
+    match node {
+        SieveNode::Unit(residual) => {
+            let mut words = vec![0u64; word_count(len)];
+            if residual.modulus != 0 {
+                // the shift is always less than the modulus, and the modulus divides `len`
+                let m = residual.modulus as usize;
+                let mut i = residual.shift as usize;
+                while i < len {
+                    words[i / WORD_BITS] |= 1 << (i % WORD_BITS);
+                    i += m;
+                }
+            }
+            words
+        }
+        SieveNode::Intersection(lhs, rhs) => {
+            let mut words = fill(lhs, len);
+            for (w, r) in words.iter_mut().zip(fill(rhs, len)) {
+                *w &= r;
+            }
+            words
+        }
+        SieveNode::Union(lhs, rhs) => {
+            let mut words = fill(lhs, len);
+            for (w, r) in words.iter_mut().zip(fill(rhs, len)) {
+                *w |= r;
+            }
+            words
+        }
+        SieveNode::SymmetricDifference(lhs, rhs) => {
+            let mut words = fill(lhs, len);
+            for (w, r) in words.iter_mut().zip(fill(rhs, len)) {
+                *w ^= r;
+            }
+            words
+        }
+        SieveNode::Difference(lhs, rhs) => {
+            let mut words = fill(lhs, len);
+            for (w, r) in words.iter_mut().zip(fill(rhs, len)) {
+                *w &= !r;
+            }
+            words
+        }
+        SieveNode::Inversion(part) => {
+            let mut words = fill(part, len);
+            for w in words.iter_mut() {
+                *w = !*w;
+            }
+            mask_tail(&mut words, len);
+            words
+        }
+    }
+}
+
+/// Return the positions of all set bits in ascending order.
+fn to_offsets(words: &[u64]) -> Vec<u64> {
+    let mut post: Vec<u64> = Vec::new();
+    for (i, &w) in words.iter().enumerate() {
+        let mut w = w;
+        while w != 0 {
+            post.push((i * WORD_BITS) as u64 + w.trailing_zeros() as u64);
+            w &= w - 1; // clear the lowest set bit
+        }
+    }
+    post
+}
+
+/// Return the index of the first member greater than or equal to `value`, where the first member greater than or equal to zero has index zero. Equivalently, for positive values, the count of members in `0..value`.
+pub(crate) fn index_of(period: u64, offsets: &[u64], value: i128) -> i128 {
+    if offsets.len() as u64 == period {
+        // every integer is a member, and the product below might overflow
+        return value;
+    }
+    let p = period as i128;
+    let count = offsets.partition_point(|&o| (o as i128) < value.rem_euclid(p));
+    // with fewer than `period` members, the product cannot overflow: for large values, its magnitude is less than that of `value`
+    value.div_euclid(p) * offsets.len() as i128 + count as i128
+}
+
+/// Return the member at the index, as counted by `index_of`, or `None` if there are no members or the member exceeds `i128`.
+pub(crate) fn value_at(period: u64, offsets: &[u64], index: i128) -> Option<i128> {
+    if offsets.is_empty() {
+        return None;
+    }
+    let n = offsets.len() as i128;
+    let p = period as i128;
+    let q = index.div_euclid(n);
+    let offset = offsets[index.rem_euclid(n) as usize] as i128;
+    if q < 0 {
+        // step back from the start of the next period, as the start of this one might precede `i128::MIN`
+        (q + 1).checked_mul(p)?.checked_sub(p - offset)
+    } else {
+        q.checked_mul(p)?.checked_add(offset)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A Sieve compiled to a lookup table of the Boolean states of one period. Membership is tested with a single remainder and bit test, rather than by evaluating each Residual. The sorted members of one period are also stored, permitting iteration that jumps directly from member to member. Created with `Sieve::compile` or `Sieve::compile_with_budget`.
+///
+/// ```
+/// let s = xensieve::Sieve::new("(5@0|4@2)&!30@10");
+/// let c = s.compile().unwrap();
+/// assert_eq!(c.period(), 60);
+/// assert_eq!(c.contains(30), true);
+/// assert_eq!(c.contains(70), false);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CompiledSieve {
+    period: u64,
+    words: Vec<u64>,
+    offsets: Vec<u64>,
+}
+
+impl CompiledSieve {
+    /// The default maximum number of bytes used by the lookup table and members of a `CompiledSieve`.
+    pub const DEFAULT_BUDGET: usize = 1 << 24;
+
+    /// Compile the node, returning a `SieveError::ExceedsBudget` if the lookup table and members require more than `budget` bytes.
+    pub(crate) fn from_node(node: &SieveNode, budget: usize) -> Result<Self, SieveError> {
+        let period = node.period()?;
+        let exceeds = SieveError::ExceedsBudget { period, budget };
+        let len: usize = period.try_into().map_err(|_e| exceeds.clone())?;
+        let bytes = word_count(len).saturating_mul(WORD_BITS / 8);
+        if bytes > budget {
+            return Err(exceeds);
+        }
+        let words = fill(node, len);
+        let count = words.iter().map(|w| w.count_ones() as usize).sum::<usize>();
+        if bytes.saturating_add(count.saturating_mul(8)) > budget {
+            return Err(exceeds);
+        }
+        Ok(Self {
+            period,
+            offsets: to_offsets(&words),
+            words,
+        })
+    }
+
+    /// Return the period of the lookup table.
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// Return the state of a position within the period.
+    #[inline(always)]
+    fn get(&self, i: usize) -> bool {
+        self.words[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0
+    }
+
+    /// Return the smallest period of the Boolean states, which must divide the period of the lookup table.
+    fn minimal_period(&self) -> usize {
+        let len = self.period as usize;
+        divisors(len)
+            .into_iter()
+            .find(|&p| (p..len).all(|i| self.get(i) == self.get(i % p)))
+            .unwrap_or(len)
+    }
+
+    /// Return the smallest period of the Boolean states and the members within it. As these depend only on the Boolean states, equivalent Sieves return the same values.
+    pub(crate) fn to_minimal_offsets(&self) -> (u64, Vec<u64>) {
+        let len = self.minimal_period() as u64;
+        let offsets = self
+            .offsets
+            .iter()
+            .copied()
+            .take_while(|&o| o < len)
+            .collect();
+        (len, offsets)
+    }
+
+    /// Return the disjoint Residuals whose union is equivalent to this Sieve. For each uncovered value, from zero, the Residual with the smallest modulus (a divisor of the minimal period) that contains only uncovered values of the Sieve is selected. As the selection depends only on the Boolean states, equivalent Sieves produce the same Residuals.
+    pub(crate) fn to_residuals(&self) -> Vec<Residual> {
+        let len = self.minimal_period();
+        let moduli = divisors(len);
+        let mut covered = vec![false; len];
+        let mut post: Vec<Residual> = Vec::new();
+
+        for x in 0..len {
+            if covered[x] || !self.get(x) {
+                continue;
+            }
+            for &m in moduli.iter() {
+                let s = x % m;
+                if (s..len).step_by(m).all(|i| !covered[i] && self.get(i)) {
+                    for i in (s..len).step_by(m) {
+                        covered[i] = true;
+                    }
+                    post.push(Residual::new(m as u64, s as u64));
+                    break;
+                }
+            }
+        }
+        post
+    }
+
+    /// Iterate, in ascending order, all values of the Sieve greater than or equal to `start`. Each value is found directly from the members of one period, such that the time taken is proportional to the number of values, not the span of integers covered.
+    ///
+    /// ```
+    /// let c = xensieve::Sieve::new("1000@0|1000@1").compile().unwrap();
+    /// assert_eq!(c.iter_from(1_000_000_000_002).take(3).collect::<Vec<_>>(), vec![1_000_000_001_000, 1_000_000_001_001, 1_000_000_002_000]);
+    /// ```
+    pub fn iter_from(&self, start: i128) -> IterMembers {
+        IterMembers::new(self.to_members(), start, None)
+    }
+
+    /// Iterate, in ascending order, all values of the Sieve within the range. As with `iter_from`, the time taken is proportional to the number of values.
+    ///
+    /// ```
+    /// let c = xensieve::Sieve::new("1000@0").compile().unwrap();
+    /// assert_eq!(c.values_in(0..10_000_000_000).count(), 10_000_000);
+    /// ```
+    pub fn values_in(&self, range: Range<i128>) -> IterMembers {
+        IterMembers::new(self.to_members(), range.start, Some(range.end))
+    }
+
+    /// Return the smallest value of the Sieve greater than `value`, or `None` if there is no such value.
+    ///
+    /// ```
+    /// let c = xensieve::Sieve::new("12@0|12@4|12@7").compile().unwrap();
+    /// assert_eq!(c.next_after(61), Some(64));
+    /// assert_eq!(c.next_after(64), Some(67));
+    /// ```
+    pub fn next_after(&self, value: i128) -> Option<i128> {
+        let index = index_of(self.period, &self.offsets, value.checked_add(1)?);
+        value_at(self.period, &self.offsets, index)
+    }
+
+    /// Return the largest value of the Sieve less than `value`, or `None` if there is no such value.
+    ///
+    /// ```
+    /// let c = xensieve::Sieve::new("12@0|12@4|12@7").compile().unwrap();
+    /// assert_eq!(c.prev_before(61), Some(60));
+    /// assert_eq!(c.prev_before(60), Some(55));
+    /// ```
+    pub fn prev_before(&self, value: i128) -> Option<i128> {
+        let index = index_of(self.period, &self.offsets, value);
+        value_at(self.period, &self.offsets, index.checked_sub(1)?)
+    }
+
+    /// Return the value of the Sieve `n` members from `start`: for `n` of zero, the first value greater than or equal to `start`; for negative `n`, values less than `start`, where -1 is the largest value less than `start`.
+    ///
+    /// ```
+    /// let c = xensieve::Sieve::new("3@0|4@0").compile().unwrap();
+    /// assert_eq!(c.nth_from(0, 0), Some(0));
+    /// assert_eq!(c.nth_from(0, 500), Some(1000));
+    /// assert_eq!(c.nth_from(10, -3), Some(6));
+    /// ```
+    pub fn nth_from(&self, start: i128, n: i128) -> Option<i128> {
+        let index = index_of(self.period, &self.offsets, start).checked_add(n)?;
+        value_at(self.period, &self.offsets, index)
+    }
+
+    /// Return the number of values of the Sieve within the range.
+    ///
+    /// ```
+    /// let c = xensieve::Sieve::new("3@0|4@0").compile().unwrap();
+    /// assert_eq!(c.count_in(0..12), 6);
+    /// assert_eq!(c.count_in(-1_000_000_000_000..1_000_000_000_000), 1_000_000_000_000);
+    /// ```
+    pub fn count_in(&self, range: Range<i128>) -> u128 {
+        if range.end <= range.start {
+            return 0;
+        }
+        let start = index_of(self.period, &self.offsets, range.start);
+        let end = index_of(self.period, &self.offsets, range.end);
+        end.abs_diff(start)
+    }
+
+    /// Return the rank of the value: the index of the first member greater than or equal to `value`, where the first member greater than or equal to zero has index zero. For positive values, this is the count of members in `0..value`; for negative values, this is the negative count of members in `value..0`. For a member, `select(rank(value))` returns `value`.
+    ///
+    /// ```
+    /// let c = xensieve::Sieve::new("3@0|4@0").compile().unwrap();
+    /// assert_eq!(c.rank(0), 0);
+    /// assert_eq!(c.rank(9), 5);
+    /// assert_eq!(c.rank(-3), -1);
+    /// ```
+    pub fn rank(&self, value: i128) -> i128 {
+        index_of(self.period, &self.offsets, value)
+    }
+
+    /// Return the member at the rank `k`, or `None` if there are no members or the member exceeds `i128`.
+    ///
+    /// ```
+    /// let c = xensieve::Sieve::new("3@0|4@0").compile().unwrap();
+    /// assert_eq!(c.select(5), Some(9));
+    /// assert_eq!(c.select(-1), Some(-3));
+    /// ```
+    pub fn select(&self, k: i128) -> Option<i128> {
+        value_at(self.period, &self.offsets, k)
+    }
+
+    /// Return a `SieveCursor` positioned at `start`.
+    pub fn cursor(&self, start: i128) -> SieveCursor {
+        SieveCursor::new(self.to_members(), start)
+    }
+
+    /// Return `true` if no values are contained in this Sieve.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Return the period and members of one period, consuming the `CompiledSieve`.
+    pub(crate) fn into_offsets(self) -> (u64, Vec<u64>) {
+        (self.period, self.offsets)
+    }
+
+    /// Return the period and members of one period as `Members`.
+    fn to_members(&self) -> Members {
+        Members::Table {
+            period: self.period,
+            offsets: self.offsets.clone(),
+        }
+    }
+
+    /// Return `true` if the value is contained with this Sieve.
+    ///
+    /// ```
+    /// let c = xensieve::Sieve::new("3@0|4@1").compile().unwrap();
+    /// assert_eq!(c.contains(-3), true);
+    /// assert_eq!(c.contains(-2), false);
+    /// ```
+    pub fn contains(&self, value: i128) -> bool {
+        self.contains_int(value)
+    }
+
+    /// Return `true` if the value, of any primitive integer type, is contained with this Sieve. See `SieveInt`.
+    pub fn contains_int<T: SieveInt>(&self, value: T) -> bool {
+        self.get(value.residue(self.period) as usize)
+    }
+
+    /// For the iterator provided as an input, iterate the subset of values that are contained within the sieve.
+    /// ```
+    /// let c = xensieve::Sieve::new("3@0|4@0").compile().unwrap();
+    /// assert_eq!(c.iter_value(0..=12).collect::<Vec<_>>(), vec![0, 3, 4, 6, 8, 9, 12])
+    /// ````
+    pub fn iter_value(
+        &self,
+        iterator: impl Iterator<Item = i128>,
+    ) -> IterValue<impl Iterator<Item = i128>> {
+        self.iter_value_int(iterator)
+    }
+
+    /// For the iterator provided as an input, of any primitive integer type, iterate the subset of values that are contained within the sieve, as the same type.
+    pub fn iter_value_int<I>(&self, iterator: I) -> IterValue<I>
+    where
+        I: Iterator,
+        I::Item: SieveInt,
+    {
+        IterValue {
+            iterator,
+            membership: Membership::Compiled(self.clone()),
+        }
+    }
+
+    /// For the iterator provided as an input, iterate the Boolean status of contained.
+    /// ```
+    /// let c = xensieve::Sieve::new("3@0|4@0").compile().unwrap();
+    /// assert_eq!(c.iter_state(0..=6).collect::<Vec<_>>(), vec![true, false, false, true, true, false, true])
+    /// ````
+    pub fn iter_state(
+        &self,
+        iterator: impl Iterator<Item = i128>,
+    ) -> IterState<impl Iterator<Item = i128>> {
+        self.iter_state_int(iterator)
+    }
+
+    /// For the iterator provided as an input, of any primitive integer type, iterate the Boolean status of contained.
+    pub fn iter_state_int<I>(&self, iterator: I) -> IterState<I>
+    where
+        I: Iterator,
+        I::Item: SieveInt,
+    {
+        IterState {
+            iterator,
+            membership: Membership::Compiled(self.clone()),
+        }
+    }
+
+    /// Iterate over integer intervals between values in the sieve.
+    /// ```
+    /// let c = xensieve::Sieve::new("3@0|4@0").compile().unwrap();
+    /// assert_eq!(c.iter_interval(0..=12).collect::<Vec<_>>(), vec![3, 1, 2, 2, 1, 3])
+    /// ````
+    pub fn iter_interval(
+        &self,
+        iterator: impl Iterator<Item = i128>,
+    ) -> IterInterval<impl Iterator<Item = i128>> {
+        self.iter_interval_int(iterator)
+    }
+
+    /// For the iterator provided as an input, of any primitive integer type, iterate over integer intervals between values in the sieve, as the same type. Iteration ends at the first interval that cannot be represented by the type.
+    pub fn iter_interval_int<I>(&self, iterator: I) -> IterInterval<I>
+    where
+        I: Iterator,
+        I::Item: SieveInt,
+    {
+        IterInterval {
+            iterator,
+            membership: Membership::Compiled(self.clone()),
+            last: PositionLast::Init,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_word_count_a() {
+        assert_eq!(word_count(0), 0);
+        assert_eq!(word_count(1), 1);
+        assert_eq!(word_count(64), 1);
+        assert_eq!(word_count(65), 2);
+    }
+
+    #[test]
+    fn test_mask_tail_a() {
+        let mut words = vec![u64::MAX, u64::MAX];
+        mask_tail(&mut words, 67);
+        assert_eq!(words, vec![u64::MAX, 0b111]);
+        mask_tail(&mut words, 128);
+        assert_eq!(words, vec![u64::MAX, 0b111]);
+    }
+
+    #[test]
+    fn test_minimal_period_a() {
+        assert_eq!(Sieve::new("3@0|6@0").compile().unwrap().minimal_period(), 3);
+        assert_eq!(Sieve::new("4@1^4@1").compile().unwrap().minimal_period(), 1);
+        assert_eq!(
+            Sieve::new("4@1|6@1").compile().unwrap().minimal_period(),
+            12
+        );
+    }
+
+    #[test]
+    fn test_to_residuals_a() {
+        let c1 = Sieve::new("3@0|4@0").compile().unwrap();
+        assert_eq!(
+            c1.to_residuals(),
+            vec![
+                Residual::new(3, 0),
+                Residual::new(12, 4),
+                Residual::new(12, 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_residuals_b() {
+        let c1 = Sieve::new("0@0").compile().unwrap();
+        assert_eq!(c1.to_residuals(), vec![]);
+        let c2 = Sieve::new("!0@0").compile().unwrap();
+        assert_eq!(c2.to_residuals(), vec![Residual::new(1, 0)]);
+    }
+
+    #[test]
+    fn test_compiled_sieve_a() {
+        let s1 = Sieve::new("!(3@0|4@1)");
+        let c1 = s1.compile().unwrap();
+        assert_eq!(c1.period(), 12);
+        for i in -30..30 {
+            assert_eq!(c1.contains(i), s1.contains(i));
+        }
+    }
+
+    #[test]
+    fn test_compiled_sieve_b() {
+        let c1 = Sieve::new("0@0").compile().unwrap();
+        assert_eq!(c1.period(), 1);
+        assert_eq!(c1.contains(0), false);
+        let c2 = Sieve::new("!0@0").compile().unwrap();
+        assert_eq!(c2.contains(0), true);
+        assert_eq!(c2.contains(i128::MIN), true);
+    }
+
+    #[test]
+    fn test_compiled_sieve_c() {
+        let s1 = Sieve::new("1000@3|1001@0");
+        assert_eq!(
+            s1.compile_with_budget(1024).unwrap_err(),
+            SieveError::ExceedsBudget {
+                period: 1001000,
+                budget: 1024
+            }
+        );
+        // the lookup table requires 125128 bytes, and the 2000 members 16000 bytes
+        assert!(s1.compile_with_budget(141127).is_err());
+        assert_eq!(s1.compile_with_budget(141128).unwrap().period(), 1001000);
+    }
+
+    #[test]
+    fn test_to_offsets_a() {
+        assert_eq!(to_offsets(&[0b1011, 0, 1]), vec![0, 1, 3, 128]);
+        assert_eq!(to_offsets(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_iter_members_a() {
+        let c1 = Sieve::new("3@0|4@0").compile().unwrap();
+        assert_eq!(
+            c1.iter_from(-13).take(6).collect::<Vec<_>>(),
+            vec![-12, -9, -8, -6, -4, -3]
+        );
+        assert_eq!(
+            c1.values_in(10..24).collect::<Vec<_>>(),
+            vec![12, 15, 16, 18, 20, 21]
+        );
+        assert_eq!(c1.values_in(10..10).collect::<Vec<_>>(), vec![]);
+    }
+
+    #[test]
+    fn test_iter_members_b() {
+        let c1 = Sieve::new("0@0").compile().unwrap();
+        assert_eq!(c1.iter_from(0).next(), None);
+        let c2 = Sieve::new("!0@0").compile().unwrap();
+        assert_eq!(c2.iter_from(5).take(3).collect::<Vec<_>>(), vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn test_iter_members_d() {
+        let c1 = Sieve::new("7@3|7@4").compile().unwrap();
+        let post1: Vec<_> = c1.iter_from(i128::MIN).take(3).collect();
+        assert_eq!(post1.len(), 3);
+        for v in post1 {
+            assert!(c1.contains(v));
+        }
+        assert_eq!(
+            c1.iter_from(i128::MIN).next(),
+            c1.iter_value(i128::MIN..).next()
+        );
+    }
+
+    #[test]
+    fn test_iter_members_c() {
+        let c1 = Sieve::new("7@3").compile().unwrap();
+        let mut it = c1.iter_from(i128::MAX - 20);
+        assert_eq!(it.by_ref().count(), 3);
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_index_of_a() {
+        let offsets = vec![0, 3, 4, 6, 8, 9];
+        assert_eq!(index_of(12, &offsets, 0), 0);
+        assert_eq!(index_of(12, &offsets, 1), 1);
+        assert_eq!(index_of(12, &offsets, 12), 6);
+        assert_eq!(index_of(12, &offsets, 13), 7);
+        assert_eq!(index_of(12, &offsets, -1), 0);
+        assert_eq!(index_of(12, &offsets, -3), -1);
+        assert_eq!(index_of(12, &offsets, -12), -6);
+        assert_eq!(index_of(12, &[], 100), 0);
+        assert_eq!(index_of(3, &[0, 1, 2], i128::MIN), i128::MIN);
+        assert_eq!(index_of(3, &[0, 1, 2], i128::MAX), i128::MAX);
+        assert_eq!(index_of(3, &[0, 2], i128::MIN), i128::MIN / 3 * 2 - 1);
+    }
+
+    #[test]
+    fn test_value_at_a() {
+        let offsets = vec![0, 3, 4, 6, 8, 9];
+        assert_eq!(value_at(12, &offsets, 0), Some(0));
+        assert_eq!(value_at(12, &offsets, 7), Some(15));
+        assert_eq!(value_at(12, &offsets, -1), Some(-3));
+        assert_eq!(value_at(12, &offsets, -6), Some(-12));
+        assert_eq!(value_at(12, &offsets, i128::MAX), None);
+        assert_eq!(value_at(12, &[], 0), None);
+        let index = index_of(3, &[1, 2], i128::MIN);
+        assert_eq!(value_at(3, &[1, 2], index), Some(i128::MIN));
+        assert_eq!(value_at(3, &[1, 2], index - 1), None);
+        assert_eq!(value_at(12, &offsets, i128::MIN), None);
+    }
+
+    #[test]
+    fn test_next_after_a() {
+        let c1 = Sieve::new("3@0|4@0").compile().unwrap();
+        assert_eq!(c1.next_after(-1), Some(0));
+        assert_eq!(c1.next_after(0), Some(3));
+        assert_eq!(c1.next_after(9), Some(12));
+        assert_eq!(c1.next_after(i128::MAX), None);
+        assert_eq!(c1.prev_before(0), Some(-3));
+        assert_eq!(c1.prev_before(1), Some(0));
+        assert_eq!(c1.prev_before(i128::MIN), None);
+    }
+
+    #[test]
+    fn test_next_after_b() {
+        let c1 = Sieve::new("0@0").compile().unwrap();
+        assert_eq!(c1.next_after(0), None);
+        assert_eq!(c1.prev_before(0), None);
+        assert_eq!(c1.nth_from(0, 3), None);
+    }
+
+    #[test]
+    fn test_nth_from_a() {
+        let c1 = Sieve::new("5@0|5@1|5@4").compile().unwrap();
+        let post1: Vec<_> = c1.iter_value(-30..30).collect();
+        // every member is found from every start
+        for start in -15..15 {
+            let i = post1.iter().position(|&v| v >= start).unwrap() as i128;
+            for n in -5..5 {
+                assert_eq!(c1.nth_from(start, n), Some(post1[(i + n) as usize]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cursor_a() {
+        let c1 = Sieve::new("3@0|4@0").compile().unwrap();
+        let mut cur = c1.cursor(0);
+        assert_eq!(cur.peek(), Some(0));
+        assert_eq!(cur.peek_prev(), Some(-3));
+        assert_eq!(cur.by_ref().take(4).collect::<Vec<_>>(), vec![0, 3, 4, 6]);
+        assert_eq!(cur.peek_prev(), Some(6));
+        let post: Vec<_> = (0..6).map(|_| cur.prev().unwrap()).collect();
+        assert_eq!(post, vec![6, 4, 3, 0, -3, -4]);
+        assert_eq!(cur.next(), Some(-4));
+        assert_eq!(cur.nth_prev(2), Some(-8));
+        assert_eq!(cur.next(), Some(-8));
+    }
+
+    #[test]
+    fn test_cursor_b() {
+        let c1 = Sieve::new("0@0").compile().unwrap();
+        let mut cur = c1.cursor(0);
+        assert_eq!(cur.next(), None);
+        assert_eq!(cur.prev(), None);
+        assert_eq!(cur.nth(4), None);
+        assert_eq!(cur.nth_prev(4), None);
+    }
+
+    #[test]
+    fn test_cursor_c() {
+        let c1 = Sieve::new("1@0").compile().unwrap();
+        let mut cur = c1.cursor(i128::MAX - 1);
+        assert_eq!(cur.next(), Some(i128::MAX - 1));
+        assert_eq!(cur.next(), Some(i128::MAX));
+        assert_eq!(cur.next(), None);
+        assert_eq!(cur.next(), None);
+        assert_eq!(cur.peek(), None);
+        assert_eq!(cur.prev(), Some(i128::MAX));
+        assert_eq!(cur.prev(), Some(i128::MAX - 1));
+        assert_eq!(cur.nth(1), Some(i128::MAX));
+        assert_eq!(cur.nth_prev(3), Some(i128::MAX - 3));
+    }
+
+    #[test]
+    fn test_count_in_a() {
+        let c1 = Sieve::new("5@0|5@1|5@4").compile().unwrap();
+        for start in -20..20 {
+            for end in start - 2..start + 30 {
+                let count = (start..end).filter(|&v| c1.contains(v)).count() as u128;
+                assert_eq!(c1.count_in(start..end), count);
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_in_b() {
+        let c1 = Sieve::new("1@0").compile().unwrap();
+        assert_eq!(c1.count_in(i128::MIN..i128::MAX), u128::MAX);
+        let c2 = Sieve::new("0@0").compile().unwrap();
+        assert_eq!(c2.count_in(i128::MIN..i128::MAX), 0);
+    }
+
+    #[test]
+    fn test_rank_a() {
+        let c1 = Sieve::new("7@0|11@3").compile().unwrap();
+        for v in c1.values_in(-200..200) {
+            assert_eq!(c1.select(c1.rank(v)), Some(v));
+        }
+        assert_eq!(c1.rank(1), 1);
+        assert_eq!(c1.rank(3), 1);
+        assert_eq!(c1.rank(4), 2);
+    }
+}
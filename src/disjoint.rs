@@ -0,0 +1,259 @@
+use crate::Residual;
+use crate::SieveError;
+use crate::SieveNode;
+
+/// Return the disjoint Residuals whose union is the Residual `r` less its sub-Residual `i`. The modulus of `r` is refined by one prime factor of the ratio of moduli at a time, retaining the classes that do not contain `i`, such that a ratio with prime factors `p1 * p2 * ...` requires `(p1 - 1) + (p2 - 1) + ...` Residuals. Returns `None` if more than `limit` Residuals are required.
+fn split(r: &Residual, i: &Residual, limit: usize) -> Option<Vec<Residual>> {
+    let mut post: Vec<Residual> = Vec::new();
+    let mut current = *r;
+    let mut q = i.modulus / r.modulus;
+    let mut p = 2;
+    while q > 1 {
+        // find the smallest prime factor of `q`; a factor greater than `limit` requires too many Residuals
+        while q % p != 0 {
+            if p > limit as u64 {
+                return None;
+            }
+            if p * p > q {
+                p = q;
+                break;
+            }
+            p += 1;
+        }
+        if post.len() as u64 + p - 1 > limit as u64 {
+            return None;
+        }
+        q /= p;
+        // as `m` divides the modulus of `i`, it cannot overflow
+        let m = current.modulus * p;
+        let mut next = current;
+        for k in 0..p {
+            let c = Residual::new(m, current.shift + k * current.modulus);
+            if c.contains(i.shift as i128) {
+                next = c;
+            } else {
+                post.push(c);
+            }
+        }
+        current = next;
+    }
+    Some(post)
+}
+
+/// Return the disjoint Residuals whose union contains the values of the disjoint Residuals `lhs` that are not contained in any of `rhs`. Returns `None` if more than `limit` Residuals, or more than `limit` intersections, are required.
+fn difference(
+    lhs: Vec<Residual>,
+    rhs: &[Residual],
+    limit: usize,
+) -> Result<Option<Vec<Residual>>, SieveError> {
+    let mut post = lhs;
+    let mut steps: usize = 0;
+    for s in rhs {
+        steps += post.len();
+        if steps > limit {
+            return Ok(None);
+        }
+        let mut next: Vec<Residual> = Vec::new();
+        for r in post {
+            match r.checked_intersection(s)? {
+                None => next.push(r),
+                Some(i) if i == r => {}
+                Some(i) => match split(&r, &i, limit) {
+                    Some(parts) => next.extend(parts),
+                    None => return Ok(None),
+                },
+            }
+            if next.len() > limit {
+                return Ok(None);
+            }
+        }
+        post = next;
+    }
+    Ok(Some(post))
+}
+
+/// Implementation of `to_disjoint`, returning `None` if more than `limit` Residuals, or more than `limit` intersections in one operation, are required.
+fn rewrite(node: &SieveNode, limit: usize) -> Result<Option<Vec<Residual>>, SieveError> {
+    let pair = |lhs: &SieveNode, rhs: &SieveNode| -> Result<_, SieveError> {
+        Ok(match (rewrite(lhs, limit)?, rewrite(rhs, limit)?) {
+            (Some(l), Some(r)) => Some((l, r)),
+            _ => None,
+        })
+    };
+    Ok(match node {
+        SieveNode::Unit(residual) if residual.modulus == 0 => Some(Vec::new()),
+        SieveNode::Unit(residual) => Some(vec![*residual]),
+        SieveNode::Intersection(lhs, rhs) => {
+            let (l, r) = match pair(lhs, rhs)? {
+                Some(p) => p,
+                None => return Ok(None),
+            };
+            if l.len().saturating_mul(r.len()) > limit {
+                return Ok(None);
+            }
+            // the intersections of disjoint Residuals are disjoint
+            let mut post: Vec<Residual> = Vec::new();
+            for a in l.iter() {
+                for b in r.iter() {
+                    if let Some(i) = a.checked_intersection(b)? {
+                        post.push(i);
+                    }
+                }
+                if post.len() > limit {
+                    return Ok(None);
+                }
+            }
+            Some(post)
+        }
+        SieveNode::Union(lhs, rhs) => {
+            let (mut l, r) = match pair(lhs, rhs)? {
+                Some(p) => p,
+                None => return Ok(None),
+            };
+            match difference(r, &l, limit)? {
+                Some(d) if l.len() + d.len() <= limit => {
+                    l.extend(d);
+                    Some(l)
+                }
+                _ => None,
+            }
+        }
+        SieveNode::SymmetricDifference(lhs, rhs) => {
+            let (l, r) = match pair(lhs, rhs)? {
+                Some(p) => p,
+                None => return Ok(None),
+            };
+            match (difference(l.clone(), &r, limit)?, difference(r, &l, limit)?) {
+                (Some(mut a), Some(b)) if a.len() + b.len() <= limit => {
+                    a.extend(b);
+                    Some(a)
+                }
+                _ => None,
+            }
+        }
+        SieveNode::Difference(lhs, rhs) => {
+            let (l, r) = match pair(lhs, rhs)? {
+                Some(p) => p,
+                None => return Ok(None),
+            };
+            difference(l, &r, limit)?
+        }
+        SieveNode::Inversion(part) => match rewrite(part, limit)? {
+            // the complement is the universal Residual less each Residual
+            Some(p) => difference(vec![Residual::new(1, 0)], &p, limit)?,
+            None => None,
+        },
+    })
+}
+
+/// Return disjoint Residuals, sorted, whose union is equivalent to the node. Rather than evaluating a period, intersections are rewritten with `Residual::checked_intersection`, and unions, differences, and inversions are made disjoint by removing the intersection from each Residual, refining its modulus only as far as needed. A `SieveError::ExceedsBudget` is returned if the Residuals require more than `budget` bytes, or if any one operation requires more intersections than that number of Residuals; a `SieveError::Overflow` is returned if the modulus of an intersection exceeds `u64`.
+pub(crate) fn to_disjoint(node: &SieveNode, budget: usize) -> Result<Vec<Residual>, SieveError> {
+    let limit = budget / std::mem::size_of::<Residual>();
+    match rewrite(node, limit)? {
+        Some(mut post) => {
+            post.sort();
+            Ok(post)
+        }
+        None => Err(SieveError::ExceedsBudget {
+            period: node.period()?,
+            budget,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_split_a() {
+        let r = Residual::new(2, 1);
+        let i = Residual::new(12, 7);
+        // the ratio of 6 is refined by 2 and then 3
+        assert_eq!(
+            split(&r, &i, 10).unwrap(),
+            vec![
+                Residual::new(4, 1),
+                Residual::new(12, 3),
+                Residual::new(12, 11)
+            ]
+        );
+        assert_eq!(split(&r, &i, 2), None);
+        assert_eq!(split(&r, &r, 10).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_split_b() {
+        let r = Residual::new(3, 0);
+        let i = Residual::new(3 * 1000003, 6);
+        assert_eq!(split(&r, &i, 1000001), None);
+        assert_eq!(split(&r, &i, 1000002).unwrap().len(), 1000002);
+    }
+
+    #[test]
+    fn test_to_disjoint_a() {
+        for expr in [
+            "3@0|4@0",
+            "!(3@0|3@1)",
+            "(6@0|4@1)&!12@0",
+            "(5@0|4@2)&!30@10",
+            "3@0^4@1^5@2",
+            "(3@0-6@0)|(8@1-24@1)",
+            "!0@0",
+            "0@0",
+        ] {
+            let s1 = Sieve::new(expr);
+            let post = to_disjoint(&s1.root, 1 << 16).unwrap();
+            for (j, a) in post.iter().enumerate() {
+                for b in post[j + 1..].iter() {
+                    assert!(a.is_disjoint(b));
+                }
+            }
+            for v in -120..120 {
+                assert_eq!(post.iter().any(|r| r.contains(v)), s1.contains(v));
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_disjoint_b() {
+        let s1 = Sieve::new("1000003@0|1000033@5");
+        let post = to_disjoint(&s1.root, 1 << 24).unwrap();
+        assert_eq!(post.len(), 1000003);
+        assert_eq!(
+            to_disjoint(&s1.root, 1 << 16).unwrap_err(),
+            SieveError::ExceedsBudget {
+                period: 1000036000099,
+                budget: 1 << 16
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_disjoint_c() {
+        let s1 = Sieve::new("1000000007@3&!1000000009@3");
+        assert_eq!(
+            to_disjoint(&s1.root, 1 << 16).unwrap_err(),
+            SieveError::ExceedsBudget {
+                period: 1000000016000000063,
+                budget: 1 << 16
+            }
+        );
+        let s2 = Sieve::new("1000000007@3&(1000000007@3|1000000007@4)");
+        assert_eq!(
+            to_disjoint(&s2.root, 1 << 16).unwrap(),
+            vec![Residual::new(1000000007, 3)]
+        );
+    }
+
+    #[test]
+    fn test_to_disjoint_d() {
+        // few Residuals are required, but the difference requires more intersections than the limit
+        let s1 = Sieve::new("997@0^(991@0|983@0|977@0)");
+        assert!(to_disjoint(&s1.root, 1 << 16).is_err());
+        let s2 = Sieve::new("(4@0|4@1)&(6@0|6@1|6@2)");
+        assert_eq!(to_disjoint(&s2.root, 6 * 16).unwrap().len(), 3);
+        assert!(to_disjoint(&s2.root, 5 * 16).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::parser;
+use crate::Residual;
+use crate::Sieve;
+use crate::SieveError;
+use crate::SieveNode;
+use crate::Span;
+
+/// A set of named Sieves that can be referenced by name in Sieve string expressions. Names start with a letter or underscore, followed by letters, digits, or underscores. Expressions can bind names with statements separated by `;`, such as `a = 3@0|4@1; b = 5@2; (a & !b) | (b ^ 12@7)`.
+///
+/// ```
+/// use xensieve::{Sieve, SieveEnv};
+/// let mut env = SieveEnv::new();
+/// env.insert("major", Sieve::new("12@0|12@2|12@4|12@5|12@7|12@9|12@11")).unwrap();
+/// let s = env.eval("tonic = 12@0; major - tonic").unwrap().unwrap();
+/// assert_eq!(s.iter_value(0..12).collect::<Vec<_>>(), vec![2, 4, 5, 7, 9, 11]);
+/// assert!(env.get("tonic").is_some());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SieveEnv {
+    names: HashMap<String, Sieve>,
+}
+
+impl SieveEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind `name` to `sieve`, returning the Sieve previously bound to `name`, if any. A `SieveError::MalformedBinding` is returned if `name` is not a valid name.
+    pub fn insert(&mut self, name: &str, sieve: Sieve) -> Result<Option<Sieve>, SieveError> {
+        if !parser::is_name(name) {
+            return Err(SieveError::MalformedBinding {
+                binding: name.to_string(),
+                span: Span::new(0, name.len()),
+            });
+        }
+        Ok(self.names.insert(name.to_string(), sieve))
+    }
+
+    /// Return the Sieve bound to `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&Sieve> {
+        self.names.get(name)
+    }
+
+    /// Remove the binding of `name`, returning the Sieve bound to `name`, if any.
+    pub fn remove(&mut self, name: &str) -> Option<Sieve> {
+        self.names.remove(name)
+    }
+
+    /// Return the bound names in ascending order.
+    pub fn names(&self) -> Vec<&str> {
+        let mut post: Vec<&str> = self.names.keys().map(|k| k.as_str()).collect();
+        post.sort_unstable();
+        post
+    }
+
+    /// Evaluate the statements of a Sieve string expression, retaining any bindings in this environment. Returns the Sieve of the last statement, or `None` if the last statement is a binding. If any statement is invalid, a `SieveError` is returned and no bindings are retained.
+    ///
+    /// ```
+    /// let mut env = xensieve::SieveEnv::new();
+    /// assert!(env.eval("a = 3@0|4@1").unwrap().is_none());
+    /// assert_eq!(env.eval("a & !6@0").unwrap().unwrap().to_string(), "Sieve{(3@0|4@1)&!(6@0)}");
+    /// assert!(env.eval("b = 5@0; c").is_err());
+    /// assert!(env.get("b").is_none());
+    /// ```
+    pub fn eval(&mut self, expr: &str) -> Result<Option<Sieve>, SieveError> {
+        let mut names = self.names.clone();
+        let post = eval_statements(&mut names, expr)?;
+        self.names = names;
+        Ok(post)
+    }
+}
+
+/// A Sieve of Residuals of type `Residual` that can be built from, and bound to names in, Sieve string expressions.
+pub(crate) trait Statements: Clone {
+    type Residual: Clone + fmt::Display;
+
+    /// Return the Residual of an operand in the form `M@S`, with the span used for error reporting.
+    fn residual(operand: &str, span: Span) -> Result<Self::Residual, SieveError>;
+
+    fn root(&self) -> &SieveNode<Self::Residual>;
+
+    fn from_root(root: SieveNode<Self::Residual>) -> Self;
+}
+
+impl Statements for Sieve {
+    type Residual = Residual;
+
+    fn residual(operand: &str, span: Span) -> Result<Residual, SieveError> {
+        let (m, s) = parser::residual_to_ints(operand, span)?;
+        Ok(Residual::new(m, s))
+    }
+
+    fn root(&self) -> &SieveNode {
+        &self.root
+    }
+
+    fn from_root(root: SieveNode) -> Self {
+        Sieve { root }
+    }
+}
+
+/// Evaluate each statement in order, binding names in `names`, and return the Sieve of the last statement if it is not a binding.
+pub(crate) fn eval_statements<T: Statements>(
+    names: &mut HashMap<String, T>,
+    expr: &str,
+) -> Result<Option<T>, SieveError> {
+    let mut post: Option<T> = None;
+    for statement in parser::split_statements(expr)? {
+        let root =
+            SieveNode::try_from_range(expr, statement.start, statement.end, &|operand, span| {
+                if parser::is_name(operand) {
+                    return match names.get(operand) {
+                        Some(s) => Ok(s.root().clone()),
+                        None => Err(SieveError::UndefinedName {
+                            name: operand.to_string(),
+                            span,
+                        }),
+                    };
+                }
+                Ok(SieveNode::Unit(T::residual(operand, span)?))
+            })?;
+        let sieve = T::from_root(root);
+        match statement.name {
+            Some(token) => {
+                names.insert(token.value, sieve);
+                post = None;
+            }
+            None => post = Some(sieve),
+        }
+    }
+    Ok(post)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sieve_env_a() {
+        let mut env = SieveEnv::new();
+        let s1 = env
+            .eval("a = 3@0|4@1; b = 5@2; (a & !b) | (b ^ 12@7)")
+            .unwrap()
+            .unwrap();
+        let s2 = Sieve::new("((3@0|4@1) & !5@2) | (5@2 ^ 12@7)");
+        assert_eq!(s1, s2);
+        assert_eq!(env.names(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_sieve_env_b() {
+        let mut env = SieveEnv::new();
+        assert_eq!(
+            env.eval("a = 3@0; a | b").unwrap_err(),
+            SieveError::UndefinedName {
+                name: String::from("b"),
+                span: Span::new(13, 1)
+            }
+        );
+        assert!(env.get("a").is_none());
+    }
+
+    #[test]
+    fn test_sieve_env_c() {
+        let mut env = SieveEnv::new();
+        assert_eq!(
+            env.eval("3a = 3@0; 3a").unwrap_err(),
+            SieveError::MalformedBinding {
+                binding: String::from("3a"),
+                span: Span::new(0, 2)
+            }
+        );
+        assert_eq!(
+            env.insert("a b", Sieve::new("3@0")).unwrap_err(),
+            SieveError::MalformedBinding {
+                binding: String::from("a b"),
+                span: Span::new(0, 3)
+            }
+        );
+    }
+
+    #[test]
+    fn test_sieve_env_d() {
+        // names can be rebound, and bindings hold the value at the time of binding
+        let mut env = SieveEnv::new();
+        env.insert("x", Sieve::new("2@0")).unwrap();
+        env.eval("y = x | 3@0; x = 5@0").unwrap();
+        assert_eq!(env.get("x").unwrap().to_string(), "Sieve{5@0}");
+        assert_eq!(env.get("y").unwrap().to_string(), "Sieve{2@0|3@0}");
+        assert_eq!(env.remove("y").unwrap().to_string(), "Sieve{2@0|3@0}");
+        assert_eq!(env.names(), vec!["x"]);
+    }
+
+    #[test]
+    fn test_sieve_env_e() {
+        let mut env = SieveEnv::new();
+        // blank statements are dropped
+        let s1 = env.eval(" ; a = 7@1 ;; a ; ").unwrap().unwrap();
+        assert_eq!(s1.to_string(), "Sieve{7@1}");
+        assert!(env.eval("").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::error::Error;
+use std::fmt;
+
+/// The location of a token within a Sieve string expression.
+///
+/// # Fields
+/// * `offset` - The byte offset of the first character of the token.
+/// * `len` - The length of the token in bytes.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn new(offset: usize, len: usize) -> Self {
+        Self { offset, len }
+    }
+
+    /// Return the smallest `Span` that covers both `self` and `other`.
+    pub(crate) fn join(&self, other: Span) -> Self {
+        let start = self.offset.min(other.offset);
+        let end = (self.offset + self.len).max(other.offset + other.len);
+        Self::new(start, end - start)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The errors that can result from constructing or evaluating a Sieve. Errors from parsing a string expression carry the `Span` of the offending token.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SieveError {
+    /// A character that is not part of the Sieve expression grammar.
+    UnsupportedCharacter { character: char, span: Span },
+    /// An operand that cannot be parsed as a Residual in the form `M@S`.
+    MalformedResidual { residual: String, span: Span },
+    /// An operator that is missing one or more of its operands.
+    MissingOperand { operator: char, span: Span },
+    /// An opening or closing parenthesis without a matching partner.
+    UnbalancedParenthesis { parenthesis: char, span: Span },
+    /// Operands that are not combined by an operator; the span is that of the first uncombined operand.
+    TrailingOperands { span: Span },
+    /// An expression that does not contain any operands.
+    EmptyExpression { span: Span },
+    /// A name that is not bound to a Sieve.
+    UndefinedName { name: String, span: Span },
+    /// A binding whose name is not a letter or underscore followed by letters, digits, or underscores.
+    MalformedBinding { binding: String, span: Span },
+    /// An arithmetic result, such as the period of a Sieve, that exceeds the supported integer range.
+    Overflow,
+    /// A lookup table for a Sieve with the given period that would require more than the given number of bytes.
+    ExceedsBudget { period: u64, budget: usize },
+    /// A value of a segment that is not within the range of the segment.
+    SegmentOutOfRange { value: i128 },
+    /// A segment range that contains more than the given limit of integers.
+    SegmentTooLong { len: u128, limit: usize },
+    /// A Residual modulus that is negative or exceeds `u64`.
+    InvalidModulus { modulus: i128 },
+}
+
+impl fmt::Display for SieveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SieveError::UnsupportedCharacter { character, span } => write!(
+                f,
+                "Found unsupported character: {character} (at {}).",
+                span.offset
+            ),
+            SieveError::MalformedResidual { residual, span } => {
+                write!(f, "Cannot parse Residual: {residual} (at {}).", span.offset)
+            }
+            SieveError::MissingOperand { operator, span } => write!(
+                f,
+                "Missing operand for operator: {operator} (at {}).",
+                span.offset
+            ),
+            SieveError::UnbalancedParenthesis { parenthesis, span } => write!(
+                f,
+                "Unbalanced parenthesis: {parenthesis} (at {}).",
+                span.offset
+            ),
+            SieveError::TrailingOperands { span } => write!(
+                f,
+                "Found operands without an operator (at {}).",
+                span.offset
+            ),
+            SieveError::EmptyExpression { .. } => write!(f, "Found an empty expression."),
+            SieveError::UndefinedName { name, span } => {
+                write!(f, "Undefined name: {name} (at {}).", span.offset)
+            }
+            SieveError::MalformedBinding { binding, span } => {
+                write!(f, "Cannot parse binding: {binding} (at {}).", span.offset)
+            }
+            SieveError::Overflow => write!(f, "Integer overflow."),
+            SieveError::ExceedsBudget { period, budget } => write!(
+                f,
+                "Period {period} exceeds the memory budget of {budget} bytes."
+            ),
+            SieveError::SegmentOutOfRange { value } => {
+                write!(f, "Segment value {value} is not within the range.")
+            }
+            SieveError::SegmentTooLong { len, limit } => write!(
+                f,
+                "Segment range of {len} integers exceeds the limit of {limit}."
+            ),
+            SieveError::InvalidModulus { modulus } => write!(f, "Invalid modulus: {modulus}."),
+        }
+    }
+}
+
+impl Error for SieveError {}
+
+impl SieveError {
+    /// Return the `Span` of the token that caused this error, if the error resulted from parsing a string expression.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            SieveError::UnsupportedCharacter { span, .. }
+            | SieveError::MalformedResidual { span, .. }
+            | SieveError::MissingOperand { span, .. }
+            | SieveError::UnbalancedParenthesis { span, .. }
+            | SieveError::TrailingOperands { span }
+            | SieveError::EmptyExpression { span }
+            | SieveError::UndefinedName { span, .. }
+            | SieveError::MalformedBinding { span, .. } => Some(*span),
+            SieveError::Overflow
+            | SieveError::ExceedsBudget { .. }
+            | SieveError::SegmentOutOfRange { .. }
+            | SieveError::SegmentTooLong { .. }
+            | SieveError::InvalidModulus { .. } => None,
+        }
+    }
+
+    /// Given the string expression that produced this error, render the error message followed by the expression with a caret under the offending token.
+    ///
+    /// ```
+    /// let e = xensieve::Sieve::try_new("3@0 | 4@1 + 5@2").unwrap_err();
+    /// assert_eq!(
+    ///     e.render("3@0 | 4@1 + 5@2"),
+    ///     "Found unsupported character: + (at 10).\n3@0 | 4@1 + 5@2\n          ^"
+    /// );
+    /// ```
+    pub fn render(&self, expr: &str) -> String {
+        let mut post = self.to_string();
+        if let Some(span) = self.span() {
+            // align the caret by characters rather than bytes
+            let start = expr.get(..span.offset).map_or(0, |s| s.chars().count());
+            let width = expr
+                .get(span.offset..span.offset + span.len)
+                .map_or(1, |s| s.chars().count())
+                .max(1);
+            post.push('\n');
+            post.push_str(expr);
+            post.push('\n');
+            post.push_str(&" ".repeat(start));
+            post.push_str(&"^".repeat(width));
+        }
+        post
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_join_a() {
+        let s1 = Span::new(2, 3);
+        let s2 = Span::new(8, 2);
+        assert_eq!(s1.join(s2), Span::new(2, 8));
+        assert_eq!(s2.join(s1), Span::new(2, 8));
+    }
+
+    #[test]
+    fn test_sieve_error_display_a() {
+        let e = SieveError::UnsupportedCharacter {
+            character: '-',
+            span: Span::new(4, 1),
+        };
+        assert_eq!(e.to_string(), "Found unsupported character: - (at 4).");
+    }
+
+    #[test]
+    fn test_sieve_error_display_b() {
+        let e = SieveError::MalformedResidual {
+            residual: String::from("3@"),
+            span: Span::new(0, 2),
+        };
+        assert_eq!(e.to_string(), "Cannot parse Residual: 3@ (at 0).");
+    }
+
+    #[test]
+    fn test_sieve_error_render_a() {
+        let e = SieveError::MalformedResidual {
+            residual: String::from("4@"),
+            span: Span::new(6, 2),
+        };
+        assert_eq!(
+            e.render("3@0 | 4@"),
+            "Cannot parse Residual: 4@ (at 6).\n3@0 | 4@\n      ^^"
+        );
+    }
+
+    #[test]
+    fn test_sieve_error_render_c() {
+        let e = SieveError::Overflow;
+        assert_eq!(e.span(), None);
+        assert_eq!(e.render("3@0"), "Integer overflow.");
+    }
+
+    #[test]
+    fn test_sieve_error_render_b() {
+        let e = SieveError::EmptyExpression {
+            span: Span::new(0, 0),
+        };
+        assert_eq!(e.render(""), "Found an empty expression.\n\n^");
+    }
+}
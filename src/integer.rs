@@ -0,0 +1,81 @@
+/// A primitive integer type that can be tested for membership in a Sieve with `contains_int`, and used to drive the Sieve iterators `iter_value_int`, `iter_state_int`, and `iter_interval_int`. As untyped integer literals default to `i32`, the methods without the `_int` suffix accept only `i128`. Signed types are widened to `i128` and unsigned types to `u128` before the remainder is taken, such that the full range of each type is supported.
+///
+/// ```
+/// let s = xensieve::Sieve::new("3@0|4@1");
+/// assert_eq!(s.contains_int(9u8), true);
+/// assert_eq!(s.contains_int(u128::MAX), true);
+/// assert_eq!(s.iter_value_int(0usize..10).collect::<Vec<usize>>(), vec![0, 1, 3, 5, 6, 9]);
+/// ```
+pub trait SieveInt: Copy {
+    /// Return the remainder of this value divided by `modulus`, always in the range `0..modulus`. The modulus must be greater than zero.
+    fn residue(self, modulus: u64) -> u64;
+
+    /// Return the difference between this value and `last`, or `None` if the difference cannot be represented by the type.
+    fn interval(self, last: Self) -> Option<Self>;
+}
+
+macro_rules! impl_sieve_int_signed {
+    ($($t:ty),*) => {
+        $(
+            impl SieveInt for $t {
+                #[inline(always)]
+                fn residue(self, modulus: u64) -> u64 {
+                    (self as i128).rem_euclid(modulus as i128) as u64
+                }
+
+                #[inline(always)]
+                fn interval(self, last: Self) -> Option<Self> {
+                    self.checked_sub(last)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_sieve_int_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl SieveInt for $t {
+                #[inline(always)]
+                fn residue(self, modulus: u64) -> u64 {
+                    (self as u128 % modulus as u128) as u64
+                }
+
+                #[inline(always)]
+                fn interval(self, last: Self) -> Option<Self> {
+                    self.checked_sub(last)
+                }
+            }
+        )*
+    };
+}
+
+impl_sieve_int_signed!(i8, i16, i32, i64, i128, isize);
+impl_sieve_int_unsigned!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_residue_a() {
+        assert_eq!((-1i8).residue(3), 2);
+        assert_eq!(i8::MIN.residue(7), (-128i128).rem_euclid(7) as u64);
+        assert_eq!(
+            i128::MIN.residue(u64::MAX),
+            i128::MIN.rem_euclid(u64::MAX as i128) as u64
+        );
+        assert_eq!(u128::MAX.residue(10), 5);
+        assert_eq!(u64::MAX.residue(u64::MAX), 0);
+        assert_eq!(7usize.residue(1), 0);
+    }
+
+    #[test]
+    fn test_interval_a() {
+        assert_eq!(10u8.interval(3), Some(7));
+        assert_eq!((-3i32).interval(-10), Some(7));
+        assert_eq!(3u8.interval(10), None);
+        assert_eq!(100i8.interval(-100), None);
+        assert_eq!(i128::MIN.interval(i128::MIN), Some(0));
+    }
+}
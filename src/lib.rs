@@ -1,29 +1,99 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::ops::Add;
 use std::ops::BitAnd;
 use std::ops::BitOr;
 use std::ops::BitXor;
+use std::ops::Mul;
+use std::ops::Neg;
 use std::ops::Not;
-
+use std::ops::Range;
+use std::ops::Sub;
+use std::str::FromStr;
+
+#[cfg(feature = "bigint")]
+mod bigint;
+mod compiled;
+mod disjoint;
+mod env;
+mod error;
+mod integer;
+mod members;
 mod parser;
+mod render;
+mod segment;
 mod util;
 
+#[cfg(feature = "bigint")]
+pub use bigint::BigResidual;
+#[cfg(feature = "bigint")]
+pub use bigint::BigSieve;
+#[cfg(feature = "bigint")]
+pub use bigint::IterBigMembers;
+pub use compiled::CompiledSieve;
+pub use env::SieveEnv;
+pub use error::SieveError;
+pub use error::Span;
+pub use integer::SieveInt;
+pub use members::IterMembers;
+pub use members::SieveCursor;
+pub use render::RenderOptions;
+pub use render::RenderRows;
+pub use render::SvgOptions;
+pub use segment::SegmentPreference;
+
+#[doc(hidden)]
+pub use parser::const_postfix;
+#[doc(hidden)]
+pub use parser::const_postfix_len;
+#[doc(hidden)]
+pub use parser::ConstToken;
+
+/// Construct a Xenakis Sieve from a string expression that is parsed at compile time. A malformed expression is reported as a compilation error, rather than as a panic at runtime, and the resulting `Sieve` is constructed without parsing the expression again. Names and bindings are not supported.
+///
+/// ```
+/// let s = xensieve::sieve!("3@0|5@1");
+/// assert_eq!(s, xensieve::Sieve::new("3@0|5@1"));
+/// assert_eq!(s.iter_value(0..10).collect::<Vec<_>>(), vec![0, 1, 3, 6, 9]);
+/// ```
+///
+/// ```compile_fail
+/// let s = xensieve::sieve!("3@0|5@");
+/// ```
+#[macro_export]
+macro_rules! sieve {
+    ($expr:expr) => {{
+        const POSTFIX: [$crate::ConstToken; $crate::const_postfix_len($expr)] =
+            $crate::const_postfix($expr);
+        $crate::Sieve::from_const_postfix(&POSTFIX)
+    }};
+}
+
 //------------------------------------------------------------------------------
 
-/// Container of integer values for the modulus and the shift of a Residual class.
+/// Container of integer values for the modulus and the shift of a Residual class. A Residual contains all integers that, divided by the modulus, have a remainder equal to the shift. A modulus of zero defines the null Residual, which contains no integers.
 ///
 /// # Fields
 /// * `modulus` - The modulus.
 /// * `shift` - The shift.
 ///
+/// ```
+/// let r = xensieve::Residual::new(3, 4);
+/// assert_eq!(r.to_string(), "3@1");
+/// assert_eq!(r.contains(-2), true);
+/// ```
 #[derive(Clone, Debug, Copy)]
-pub(crate) struct Residual {
+pub struct Residual {
     modulus: u64,
     shift: u64,
 }
 
 impl Residual {
-    pub(crate) fn new(modulus: u64, mut shift: u64) -> Self {
+    /// Construct a Residual, normalizing the shift to be less than the modulus.
+    pub fn new(modulus: u64, mut shift: u64) -> Self {
         if modulus == 0 {
             shift = 0;
         } else {
@@ -32,14 +102,178 @@ impl Residual {
         Self { modulus, shift }
     }
 
-    /// Return `true` if the value is contained with this Sieve.
+    /// Construct a Residual from signed integers, normalizing a negative or large shift to be less than the modulus. A `SieveError::InvalidModulus` is returned if the modulus is negative or exceeds `u64`.
+    ///
+    /// ```
+    /// let r = xensieve::Residual::try_new(3, -1).unwrap();
+    /// assert_eq!(r.to_string(), "3@2");
+    /// assert!(xensieve::Residual::try_new(-3, 1).is_err());
+    /// ```
+    pub fn try_new(modulus: i128, shift: i128) -> Result<Self, SieveError> {
+        let m: u64 = modulus
+            .try_into()
+            .map_err(|_e| SieveError::InvalidModulus { modulus })?;
+        if m == 0 {
+            return Ok(Self::new(0, 0));
+        }
+        Ok(Self::new(m, shift.rem_euclid(modulus) as u64))
+    }
+
+    /// Return the modulus.
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// Return the shift, always less than the modulus.
+    pub fn shift(&self) -> u64 {
+        self.shift
+    }
+
+    /// Return `true` if the value is contained with this Residual.
+    ///
+    pub fn contains(&self, value: i128) -> bool {
+        self.contains_int(value)
+    }
+
+    /// Return `true` if the value, of any primitive integer type, is contained with this Residual.
+    ///
+    pub fn contains_int<T: SieveInt>(&self, value: T) -> bool {
+        if self.modulus == 0 {
+            return false;
+        }
+        value.residue(self.modulus) == self.shift
+    }
+
+    /// Return the smallest value of this Residual greater than `value`, or `None` if this is the null Residual or the value exceeds `i128`.
+    ///
+    /// ```
+    /// let r = xensieve::Residual::new(12, 4);
+    /// assert_eq!(r.next_after(61), Some(64));
+    /// assert_eq!(r.next_after(64), Some(76));
+    /// ```
+    pub fn next_after(&self, value: i128) -> Option<i128> {
+        if self.modulus == 0 {
+            return None;
+        }
+        let m = self.modulus as i128;
+        let start = value.checked_add(1)?;
+        start.checked_add((self.shift as i128 - start.rem_euclid(m)).rem_euclid(m))
+    }
+
+    /// Return the Residual of values contained in both Residuals, or `None` if they have no values in common. A `SieveError::Overflow` is returned if the modulus of the intersection exceeds `u64`.
+    ///
+    /// ```
+    /// let r1 = xensieve::Residual::new(u64::MAX, 0);
+    /// let r2 = xensieve::Residual::new(2, 0);
+    /// assert!(r1.checked_intersection(&r2).is_err());
+    /// ```
+    pub fn checked_intersection(&self, other: &Self) -> Result<Option<Self>, SieveError> {
+        let (m, s) = util::intersection(self.modulus, other.modulus, self.shift, other.shift)?;
+        if m == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(m, s)))
+    }
+
+    /// Return the Residual of values contained in both Residuals, or `None` if they have no values in common. Panics if the modulus of the intersection exceeds `u64`; see `checked_intersection`.
+    ///
+    /// ```
+    /// let r1 = xensieve::Residual::new(4, 0);
+    /// let r2 = xensieve::Residual::new(3, 1);
+    /// assert_eq!(r1.intersection(&r2), Some(xensieve::Residual::new(12, 4)));
+    /// assert_eq!(r1.intersection(&xensieve::Residual::new(2, 1)), None);
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.checked_intersection(other)
+            .unwrap_or_else(|e| panic!("Invalid intersection: {e}"))
+    }
+
+    /// Return `true` if the Residuals have no values in common.
+    ///
+    /// ```
+    /// let r1 = xensieve::Residual::new(4, 0);
+    /// assert_eq!(r1.is_disjoint(&xensieve::Residual::new(6, 1)), true);
+    /// assert_eq!(r1.is_disjoint(&xensieve::Residual::new(6, 2)), false);
+    /// ```
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        if self.modulus == 0 || other.modulus == 0 {
+            return true;
+        }
+        // the classes intersect if and only if the difference of shifts is divisible by the greatest common divisor
+        let d = util::gcd(self.modulus, other.modulus, 0).unwrap();
+        self.shift.abs_diff(other.shift) % d != 0
+    }
+
+    /// Return `true` if all values of this Residual are contained in the other Residual. The null Residual is a subset of all Residuals.
     ///
-    pub(crate) fn contains(&self, value: i128) -> bool {
+    /// ```
+    /// let r1 = xensieve::Residual::new(12, 7);
+    /// assert_eq!(r1.is_subset_of(&xensieve::Residual::new(4, 3)), true);
+    /// assert_eq!(r1.is_subset_of(&xensieve::Residual::new(8, 7)), false);
+    /// ```
+    pub fn is_subset_of(&self, other: &Self) -> bool {
         if self.modulus == 0 {
+            return true;
+        }
+        if other.modulus == 0 {
             return false;
         }
-        let pos: i128 = value - self.shift as i128;
-        pos % self.modulus as i128 == 0
+        self.modulus % other.modulus == 0 && self.shift % other.modulus == other.shift
+    }
+
+    /// Return the Residual of all values of this Residual plus `n`.
+    ///
+    /// ```
+    /// let r = xensieve::Residual::new(12, 4);
+    /// assert_eq!(r.shifted(10).to_string(), "12@2");
+    /// assert_eq!(r.shifted(-5).to_string(), "12@11");
+    /// ```
+    pub fn shifted(&self, n: i128) -> Self {
+        if self.modulus == 0 {
+            return *self;
+        }
+        let m = self.modulus as i128;
+        Self::new(
+            self.modulus,
+            (self.shift as i128 + n.rem_euclid(m)).rem_euclid(m) as u64,
+        )
+    }
+
+    /// Return the Residual of the negation of all values of this Residual.
+    ///
+    /// ```
+    /// let r = xensieve::Residual::new(12, 4);
+    /// assert_eq!(r.reflected().to_string(), "12@8");
+    /// ```
+    pub fn reflected(&self) -> Self {
+        if self.modulus == 0 {
+            return *self;
+        }
+        Self::new(self.modulus, (self.modulus - self.shift) % self.modulus)
+    }
+
+    /// Return the Residual of all values of this Residual multiplied by `k`. A `SieveError::Overflow` is returned if the scaled modulus exceeds `u64`; a `SieveError::InvalidModulus` is returned if `k` is zero.
+    ///
+    /// ```
+    /// let r = xensieve::Residual::new(3, 1);
+    /// assert_eq!(r.checked_scaled(4).unwrap().to_string(), "12@4");
+    /// ```
+    pub fn checked_scaled(&self, k: u64) -> Result<Self, SieveError> {
+        if k == 0 {
+            return Err(SieveError::InvalidModulus { modulus: 0 });
+        }
+        let m = self.modulus.checked_mul(k).ok_or(SieveError::Overflow)?;
+        // as the shift is less than the modulus, the scaled shift cannot overflow
+        Ok(Self::new(m, self.shift * k))
+    }
+}
+
+impl FromStr for Residual {
+    type Err = SieveError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (m, s) = parser::residual_to_ints(value, Span::new(0, value.len()))?;
+        Ok(Self::new(m, s))
     }
 }
 
@@ -53,9 +287,12 @@ impl fmt::Display for Residual {
 impl BitAnd for Residual {
     type Output = Residual;
 
+    /// Panics if the modulus of the intersection exceeds `u64`; see `Residual::checked_intersection`.
     fn bitand(self, rhs: Self) -> Self::Output {
-        let (m, s) = util::intersection(self.modulus, rhs.modulus, self.shift, rhs.shift).unwrap();
-        Self::new(m, s)
+        match util::intersection(self.modulus, rhs.modulus, self.shift, rhs.shift) {
+            Ok((m, s)) => Self::new(m, s),
+            Err(e) => panic!("Invalid intersection: {e}"),
+        }
     }
 }
 
@@ -67,6 +304,13 @@ impl PartialEq for Residual {
 
 impl Eq for Residual {}
 
+impl Hash for Residual {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.modulus.hash(state);
+        self.shift.hash(state);
+    }
+}
+
 impl PartialOrd for Residual {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -83,54 +327,221 @@ impl Ord for Residual {
 
 //------------------------------------------------------------------------------
 
-/// A node in the graph of Residuals combined by logical operations.
+/// A node in the graph of Residuals combined by logical operations. The Residual type is generic such that alternative integer backends can share the same graph.
 ///
 #[derive(Clone, Debug)]
-pub(crate) enum SieveNode {
-    Unit(Residual),
-    Intersection(Box<SieveNode>, Box<SieveNode>),
-    Union(Box<SieveNode>, Box<SieveNode>),
-    SymmetricDifference(Box<SieveNode>, Box<SieveNode>),
-    Inversion(Box<SieveNode>),
+pub(crate) enum SieveNode<R = Residual> {
+    Unit(R),
+    Intersection(Box<SieveNode<R>>, Box<SieveNode<R>>),
+    Union(Box<SieveNode<R>>, Box<SieveNode<R>>),
+    SymmetricDifference(Box<SieveNode<R>>, Box<SieveNode<R>>),
+    Difference(Box<SieveNode<R>>, Box<SieveNode<R>>),
+    Inversion(Box<SieveNode<R>>),
 }
 
-impl fmt::Display for SieveNode {
+impl<R: fmt::Display> fmt::Display for SieveNode<R> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s: String = match self {
             SieveNode::Unit(residual) => residual.to_string(),
+            SieveNode::Intersection(lhs, rhs) => self.fmt_binary('&', lhs, rhs),
+            SieveNode::Union(lhs, rhs) => self.fmt_binary('|', lhs, rhs),
+            SieveNode::SymmetricDifference(lhs, rhs) => self.fmt_binary('^', lhs, rhs),
+            SieveNode::Difference(lhs, rhs) => self.fmt_binary('-', lhs, rhs),
+            SieveNode::Inversion(part) => {
+                let r = part.to_string();
+                format!("!({r})")
+            }
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl<R: fmt::Display> SieveNode<R> {
+    /// Build a node from the byte range `start..end` of a string expression, using `operand` to create a node from each operand and its span.
+    fn try_from_range<F>(
+        value: &str,
+        start: usize,
+        end: usize,
+        operand: &F,
+    ) -> Result<Self, SieveError>
+    where
+        F: Fn(&str, Span) -> Result<Self, SieveError>,
+    {
+        // each stack entry retains the span of its sub-expression for error reporting
+        let mut stack: Vec<(Self, Span)> = Vec::new();
+        for token in parser::infix_to_postfix_range(value, start, end)? {
+            let missing = || SieveError::MissingOperand {
+                operator: token.value.chars().next().unwrap(),
+                span: token.span,
+            };
+            match token.value.as_str() {
+                "!" => {
+                    let (n, span) = stack.pop().ok_or_else(missing)?;
+                    stack.push((SieveNode::Inversion(Box::new(n)), token.span.join(span)));
+                }
+                "&" | "^" | "|" | "-" => {
+                    let (right, right_span) = stack.pop().ok_or_else(missing)?;
+                    let (left, left_span) = stack.pop().ok_or_else(missing)?;
+                    let (left, right) = (Box::new(left), Box::new(right));
+                    let n = match token.value.as_str() {
+                        "&" => SieveNode::Intersection(left, right),
+                        "^" => SieveNode::SymmetricDifference(left, right),
+                        "-" => SieveNode::Difference(left, right),
+                        _ => SieveNode::Union(left, right),
+                    };
+                    stack.push((n, left_span.join(right_span)));
+                }
+                value => {
+                    stack.push((operand(value, token.span)?, token.span));
+                }
+            }
+        }
+        if stack.len() > 1 {
+            return Err(SieveError::TrailingOperands { span: stack[1].1 });
+        }
+        match stack.pop() {
+            Some((n, _)) => Ok(n),
+            None => Err(SieveError::EmptyExpression {
+                span: Span::new(start, end - start),
+            }),
+        }
+    }
+
+    /// Return a node of the same structure, with each Residual converted by `f`.
+    fn try_map<S, F>(&self, f: &F) -> Result<SieveNode<S>, SieveError>
+    where
+        F: Fn(&R) -> Result<S, SieveError>,
+    {
+        let pair = |lhs: &Self, rhs: &Self| -> Result<_, SieveError> {
+            Ok((Box::new(lhs.try_map(f)?), Box::new(rhs.try_map(f)?)))
+        };
+        Ok(match self {
+            SieveNode::Unit(residual) => SieveNode::Unit(f(residual)?),
             SieveNode::Intersection(lhs, rhs) => {
-                let lhs_str = lhs.to_string();
-                let rhs_str = rhs.to_string();
-                format!("{lhs_str}&{rhs_str}")
+                let (l, r) = pair(lhs, rhs)?;
+                SieveNode::Intersection(l, r)
             }
             SieveNode::Union(lhs, rhs) => {
-                let lhs_str = lhs.to_string();
-                let rhs_str = rhs.to_string();
-                format!("{lhs_str}|{rhs_str}")
+                let (l, r) = pair(lhs, rhs)?;
+                SieveNode::Union(l, r)
             }
             SieveNode::SymmetricDifference(lhs, rhs) => {
-                let lhs_str = lhs.to_string();
-                let rhs_str = rhs.to_string();
-                format!("{lhs_str}^{rhs_str}")
+                let (l, r) = pair(lhs, rhs)?;
+                SieveNode::SymmetricDifference(l, r)
             }
-            SieveNode::Inversion(part) => {
-                let r = part.to_string();
-                format!("!({r})")
+            SieveNode::Difference(lhs, rhs) => {
+                let (l, r) = pair(lhs, rhs)?;
+                SieveNode::Difference(l, r)
             }
+            SieveNode::Inversion(part) => SieveNode::Inversion(Box::new(part.try_map(f)?)),
+        })
+    }
+
+    /// Return the precedence of the operator of this node, as used by the parser. A Unit binds tighter than any operator.
+    fn precedence(&self) -> i8 {
+        match self {
+            SieveNode::Unit(_) => i8::MAX,
+            SieveNode::Intersection(..) => parser::char_to_precedence('&'),
+            SieveNode::Union(..) => parser::char_to_precedence('|'),
+            SieveNode::SymmetricDifference(..) => parser::char_to_precedence('^'),
+            SieveNode::Difference(..) => parser::char_to_precedence('-'),
+            SieveNode::Inversion(_) => parser::char_to_precedence('!'),
+        }
+    }
+
+    /// Format a binary operation, adding parentheses to operands that bind more loosely than this node. As operators are parsed left-associatively, a right operand of equal precedence is also enclosed, such that the string parses back to the same tree.
+    fn fmt_binary(&self, op: char, lhs: &Self, rhs: &Self) -> String {
+        let p = self.precedence();
+        let lhs_str = if lhs.precedence() < p {
+            format!("({lhs})")
+        } else {
+            lhs.to_string()
         };
-        write!(f, "{}", s)
+        let rhs_str = if rhs.precedence() <= p {
+            format!("({rhs})")
+        } else {
+            rhs.to_string()
+        };
+        format!("{lhs_str}{op}{rhs_str}")
+    }
+}
+
+impl<R: Clone> SieveNode<R> {
+    /// Return the union of the Residuals, or the `null` Residual if there are no Residuals. Runs of Residuals are combined from left to right, as they are parsed; to bound the depth of the tree, longer unions are combined from balanced halves of such runs.
+    fn from_residuals(residuals: Vec<R>, null: R) -> Self {
+        const RUN: usize = 256;
+        fn union<R: Clone>(residuals: &[R], null: R) -> SieveNode<R> {
+            if residuals.len() > RUN {
+                let mid = (residuals.len() + RUN - 1) / RUN / 2 * RUN;
+                return SieveNode::Union(
+                    Box::new(union(&residuals[..mid], null.clone())),
+                    Box::new(union(&residuals[mid..], null)),
+                );
+            }
+            residuals
+                .iter()
+                .map(|r| SieveNode::Unit(r.clone()))
+                .reduce(|lhs, rhs| SieveNode::Union(Box::new(lhs), Box::new(rhs)))
+                .unwrap_or(SieveNode::Unit(null))
+        }
+        union(&residuals, null)
     }
 }
 
 impl SieveNode {
+    /// Return a node of all values multiplied by `k`. As the values outside of an inverted node are not multiples of `k`, an inversion is intersected with `k@0`.
+    fn scaled(&self, k: u64) -> Result<Self, SieveError> {
+        let pair = |lhs: &Self, rhs: &Self| -> Result<_, SieveError> {
+            Ok((Box::new(lhs.scaled(k)?), Box::new(rhs.scaled(k)?)))
+        };
+        Ok(match self {
+            SieveNode::Unit(residual) => SieveNode::Unit(residual.checked_scaled(k)?),
+            SieveNode::Intersection(lhs, rhs) => {
+                let (l, r) = pair(lhs, rhs)?;
+                SieveNode::Intersection(l, r)
+            }
+            SieveNode::Union(lhs, rhs) => {
+                let (l, r) = pair(lhs, rhs)?;
+                SieveNode::Union(l, r)
+            }
+            SieveNode::SymmetricDifference(lhs, rhs) => {
+                let (l, r) = pair(lhs, rhs)?;
+                SieveNode::SymmetricDifference(l, r)
+            }
+            SieveNode::Difference(lhs, rhs) => {
+                let (l, r) = pair(lhs, rhs)?;
+                SieveNode::Difference(l, r)
+            }
+            SieveNode::Inversion(part) => SieveNode::Intersection(
+                Box::new(SieveNode::Unit(Residual::new(k, 0))),
+                Box::new(SieveNode::Inversion(Box::new(part.scaled(k)?))),
+            ),
+        })
+    }
+
+    /// Return the least common multiple of the non-zero moduli of all Residuals in this node, or 1 if there are none.
+    fn period(&self) -> Result<u64, SieveError> {
+        match self {
+            SieveNode::Unit(residual) => Ok(residual.modulus.max(1)),
+            SieveNode::Intersection(lhs, rhs)
+            | SieveNode::Union(lhs, rhs)
+            | SieveNode::SymmetricDifference(lhs, rhs)
+            | SieveNode::Difference(lhs, rhs) => {
+                util::lcm(lhs.period()?, rhs.period()?).map_err(|_e| SieveError::Overflow)
+            }
+            SieveNode::Inversion(part) => part.period(),
+        }
+    }
+
     /// Return `true` if the values is contained within this Sieve.
     ///
-    pub fn contains(&self, value: i128) -> bool {
+    pub fn contains<T: SieveInt>(&self, value: T) -> bool {
         match self {
-            SieveNode::Unit(residual) => residual.contains(value),
+            SieveNode::Unit(residual) => residual.contains_int(value),
             SieveNode::Intersection(lhs, rhs) => lhs.contains(value) && rhs.contains(value),
             SieveNode::Union(lhs, rhs) => lhs.contains(value) || rhs.contains(value),
             SieveNode::SymmetricDifference(lhs, rhs) => lhs.contains(value) ^ rhs.contains(value),
+            SieveNode::Difference(lhs, rhs) => lhs.contains(value) && !rhs.contains(value),
             SieveNode::Inversion(part) => !part.contains(value),
         }
     }
@@ -139,6 +550,10 @@ impl SieveNode {
 //------------------------------------------------------------------------------
 
 /// The representation of a Xenakis Sieve, constructed from a string notation of one or more Residual classes combined with logical operators. This Rust implementation follows the Python implementation in Ariza (2005), with significant performance and interface enhancements: https://direct.mit.edu/comj/article/29/2/40/93957
+///
+/// # Panics
+///
+/// Sieves are compared with `==` by the values they contain, which is decided over their combined period, or, beyond the budget of `Sieve::compile`, from their disjoint Residuals. Comparing Sieves whose Residuals cannot be made disjoint within that budget, such as `1000000007@0|1000000009@0`, panics; use `Sieve::is_equivalent` to receive a `SieveError` instead. Hashing never panics.
 #[derive(Clone, Debug)]
 pub struct Sieve {
     root: SieveNode,
@@ -215,78 +630,672 @@ impl Not for Sieve {
             root: SieveNode::Inversion(Box::new(self.root)),
         }
     }
-}
+}
+
+impl Not for &Sieve {
+    type Output = Sieve;
+
+    fn not(self) -> Self::Output {
+        Sieve {
+            root: SieveNode::Inversion(Box::new(self.root.clone())),
+        }
+    }
+}
+
+impl Sub for Sieve {
+    type Output = Sieve;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Sieve {
+            root: SieveNode::Difference(Box::new(self.root), Box::new(rhs.root)),
+        }
+    }
+}
+
+impl Sub for &Sieve {
+    type Output = Sieve;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Sieve {
+            root: SieveNode::Difference(Box::new(self.root.clone()), Box::new(rhs.root.clone())),
+        }
+    }
+}
+
+impl Add<i128> for Sieve {
+    type Output = Sieve;
+
+    fn add(self, rhs: i128) -> Self::Output {
+        self.shifted(rhs)
+    }
+}
+
+impl Add<i128> for &Sieve {
+    type Output = Sieve;
+
+    fn add(self, rhs: i128) -> Self::Output {
+        self.shifted(rhs)
+    }
+}
+
+impl Sub<i128> for Sieve {
+    type Output = Sieve;
+
+    fn sub(self, rhs: i128) -> Self::Output {
+        (&self).sub(rhs)
+    }
+}
+
+impl Sub<i128> for &Sieve {
+    type Output = Sieve;
+
+    fn sub(self, rhs: i128) -> Self::Output {
+        match rhs.checked_neg() {
+            Some(n) => self.shifted(n),
+            // the negation of i128::MIN is i128::MAX + 1
+            None => self.shifted(i128::MAX).shifted(1),
+        }
+    }
+}
+
+impl Mul<u64> for Sieve {
+    type Output = Sieve;
+
+    fn mul(self, rhs: u64) -> Self::Output {
+        self.scaled(rhs)
+    }
+}
+
+impl Mul<u64> for &Sieve {
+    type Output = Sieve;
+
+    fn mul(self, rhs: u64) -> Self::Output {
+        self.scaled(rhs)
+    }
+}
+
+impl Neg for Sieve {
+    type Output = Sieve;
+
+    fn neg(self) -> Self::Output {
+        self.reflected()
+    }
+}
+
+impl Neg for &Sieve {
+    type Output = Sieve;
+
+    fn neg(self) -> Self::Output {
+        self.reflected()
+    }
+}
+
+impl PartialEq for Sieve {
+    /// Sieves are equal if they contain the same values, regardless of their Residuals and operators. Panics if the combined period exceeds that permitted by `Sieve::compile` and the Residuals cannot be made disjoint within that budget; see `is_equivalent`.
+    fn eq(&self, other: &Self) -> bool {
+        match self.is_equivalent(other) {
+            Ok(b) => b,
+            Err(e) => panic!("Invalid comparison: {e}"),
+        }
+    }
+}
+
+impl Eq for Sieve {}
+
+impl Hash for Sieve {
+    /// Hash the smallest period of the Boolean states and the members within it, such that equal Sieves have equal hashes. Where the period exceeds that permitted by `compile`, the Boolean states of the integers `0..64` are hashed instead: hashing never panics, but such Sieves collide if they differ only beyond these integers, and might not hash equal to an equal Sieve within the budget.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if let Ok(c) = CompiledSieve::from_node(&self.root, CompiledSieve::DEFAULT_BUDGET) {
+            c.to_minimal_offsets().hash(state);
+            return;
+        }
+        let mut states: u64 = 0;
+        for v in 0..64 {
+            if self.root.contains(v as i128) {
+                states |= 1 << v;
+            }
+        }
+        states.hash(state);
+    }
+}
+
+impl fmt::Display for Sieve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sieve{{{}}}", self.root)
+    }
+}
+
+impl From<Residual> for Sieve {
+    fn from(residual: Residual) -> Self {
+        Self {
+            root: SieveNode::Unit(residual),
+        }
+    }
+}
+
+impl FromStr for Sieve {
+    type Err = SieveError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_new(value)
+    }
+}
+
+impl Sieve {
+    /// Construct a Xenakis Sieve from a string representation.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|5@1");
+    /// assert_eq!(s.iter_value(0..15).collect::<Vec<_>>(), vec![0, 1, 3, 6, 9, 11, 12])
+    /// ````
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string representation cannot be parsed; use `try_new` to handle a `SieveError`.
+    pub fn new(value: &str) -> Self {
+        match Self::try_new(value) {
+            Ok(s) => s,
+            Err(e) => panic!("Invalid syntax: {e}"),
+        }
+    }
+
+    /// Construct a Xenakis Sieve from a string representation, returning a `SieveError` if the string representation cannot be parsed. The string representation may bind names to sub-expressions with statements separated by `;`; see `SieveEnv`.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::try_new("3@0|5@1").unwrap();
+    /// assert_eq!(s.iter_value(0..6).collect::<Vec<_>>(), vec![0, 1, 3]);
+    ///
+    /// let s = xensieve::Sieve::try_new("a = 3@0|5@1; a - 15@0").unwrap();
+    /// assert_eq!(s.iter_value(0..16).collect::<Vec<_>>(), vec![1, 3, 6, 9, 11, 12]);
+    ///
+    /// let e = xensieve::Sieve::try_new("3@0|").unwrap_err();
+    /// assert_eq!(e.span(), Some(xensieve::Span::new(3, 1)));
+    /// ````
+    pub fn try_new(value: &str) -> Result<Self, SieveError> {
+        match env::eval_statements(&mut HashMap::new(), value)? {
+            Some(s) => Ok(s),
+            // the last statement is a binding
+            None => Err(SieveError::EmptyExpression {
+                span: Span::new(value.len(), 0),
+            }),
+        }
+    }
+
+    /// Construct a Xenakis Sieve from a single Residual.
+    ///
+    /// ```
+    /// use xensieve::{Residual, Sieve};
+    /// let s = Sieve::from_residual(Residual::new(3, 0)) | Sieve::from_residual(Residual::new(5, 1));
+    /// assert_eq!(s.to_string(), "Sieve{3@0|5@1}");
+    /// ```
+    pub fn from_residual(residual: Residual) -> Self {
+        Self::from(residual)
+    }
+
+    /// Construct a Xenakis Sieve from the tokens of an expression in postfix order, as validated by `const_postfix`. Used by the `sieve!` macro.
+    #[doc(hidden)]
+    pub fn from_const_postfix(tokens: &[ConstToken]) -> Self {
+        let mut stack: Vec<SieveNode> = Vec::new();
+        for token in tokens {
+            let n = match *token {
+                ConstToken::Residual(m, s) => SieveNode::Unit(Residual::new(m, s)),
+                ConstToken::Operator(b'!') => {
+                    SieveNode::Inversion(Box::new(stack.pop().expect("Missing operand")))
+                }
+                ConstToken::Operator(op) => {
+                    let right = Box::new(stack.pop().expect("Missing operand"));
+                    let left = Box::new(stack.pop().expect("Missing operand"));
+                    match op {
+                        b'&' => SieveNode::Intersection(left, right),
+                        b'^' => SieveNode::SymmetricDifference(left, right),
+                        b'-' => SieveNode::Difference(left, right),
+                        _ => SieveNode::Union(left, right),
+                    }
+                }
+            };
+            stack.push(n);
+        }
+        Self {
+            root: stack.pop().expect("Found an empty expression"),
+        }
+    }
+
+    /// The maximum number of integers in the range of a segment passed to `from_segment` or `from_segment_with`. As the Residuals of all moduli up to the span of the segment are evaluated, inference time grows with the square of the range.
+    pub const MAX_SEGMENT_LEN: usize = 1 << 13;
+
+    /// Construct a Xenakis Sieve from a segment of integers, such that iterating the Sieve over the range produces the sorted values of the segment. Residuals are selected to approximate the fewest Residuals; see `from_segment_with` to select other preferences. A `SieveError::SegmentOutOfRange` is returned if a value is not within the range; a `SieveError::SegmentTooLong` is returned if the range contains more than `Sieve::MAX_SEGMENT_LEN` integers.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::from_segment(&[0, 3, 4, 6, 8, 9], 0..12).unwrap();
+    /// assert_eq!(s.to_string(), "Sieve{3@0|4@0}");
+    /// assert_eq!(s.iter_value(0..12).collect::<Vec<_>>(), vec![0, 3, 4, 6, 8, 9]);
+    /// ```
+    pub fn from_segment(values: &[i128], range: Range<i128>) -> Result<Self, SieveError> {
+        Self::from_segment_with(values, range, SegmentPreference::FewestResiduals)
+    }
+
+    /// Construct a Xenakis Sieve from a segment of integers, such that iterating the Sieve over the range produces the sorted values of the segment, selecting Residuals with the provided `SegmentPreference`.
+    ///
+    /// ```
+    /// use xensieve::{Sieve, SegmentPreference};
+    /// let s = Sieve::from_segment_with(&[1, 4, 9, 10], 0..12, SegmentPreference::SmallestModuli).unwrap();
+    /// assert_eq!(s.to_string(), "Sieve{8@1|5@4|6@4}");
+    ///
+    /// let s = Sieve::from_segment_with(&[1, 4, 9, 10], 0..12, SegmentPreference::FewestResiduals).unwrap();
+    /// assert_eq!(s.to_string(), "Sieve{5@4|9@1}");
+    /// ```
+    pub fn from_segment_with(
+        values: &[i128],
+        range: Range<i128>,
+        preference: SegmentPreference,
+    ) -> Result<Self, SieveError> {
+        let residuals =
+            segment::segment_to_residuals(values, range, preference, Self::MAX_SEGMENT_LEN)?;
+        Ok(Self {
+            root: SieveNode::from_residuals(residuals, Residual::new(0, 0)),
+        })
+    }
+
+    /// Return `true` if the value is contained with this Sieve.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0 & 5@0");
+    /// assert_eq!(s.contains(15), true);
+    /// assert_eq!(s.contains(16), false);
+    /// assert_eq!(s.contains(30), true);
+    /// ```
+    pub fn contains(&self, value: i128) -> bool {
+        self.root.contains(value)
+    }
+
+    /// Return `true` if the value, of any primitive integer type, is contained with this Sieve. See `SieveInt`.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0 & 5@0");
+    /// assert_eq!(s.contains_int(30u8), true);
+    /// assert_eq!(s.contains_int(u128::MAX), true);
+    /// assert_eq!(s.contains_int(u128::MAX - 1), false);
+    /// ```
+    pub fn contains_int<T: SieveInt>(&self, value: T) -> bool {
+        self.root.contains(value)
+    }
+
+    /// Return the period of this Sieve, the least common multiple of the moduli of all its Residuals; the Sieve repeats every period. A `SieveError::Overflow` is returned if the period exceeds `u64`.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("(3@0|4@1)&!6@3");
+    /// assert_eq!(s.period().unwrap(), 12);
+    /// ```
+    pub fn period(&self) -> Result<u64, SieveError> {
+        self.root.period()
+    }
+
+    /// Return a Sieve of all values of this Sieve plus `n`, transposing the Sieve. This is also available with the `+` and `-` operators.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!(s.shifted(2).to_string(), "Sieve{3@2|4@3}");
+    /// assert_eq!((&s - 1).to_string(), "Sieve{3@2|4@0}");
+    /// ```
+    pub fn shifted(&self, n: i128) -> Self {
+        let root = self.root.try_map(&|r: &Residual| Ok(r.shifted(n))).unwrap();
+        Self { root }
+    }
+
+    /// Return a Sieve of all values of this Sieve multiplied by `k`, augmenting the Sieve. This is also available with the `*` operator. Panics if a scaled modulus exceeds `u64` or if `k` is zero; see `checked_scaled`.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!(s.scaled(2).to_string(), "Sieve{6@0|8@2}");
+    /// assert_eq!((&s * 2).iter_value(0..12).collect::<Vec<_>>(), vec![0, 2, 6, 10]);
+    /// ```
+    pub fn scaled(&self, k: u64) -> Self {
+        match self.checked_scaled(k) {
+            Ok(s) => s,
+            Err(e) => panic!("Invalid scale: {e}"),
+        }
+    }
+
+    /// Return a Sieve of all values of this Sieve multiplied by `k`. A `SieveError::Overflow` is returned if a scaled modulus exceeds `u64`; a `SieveError::InvalidModulus` is returned if `k` is zero.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("!3@0");
+    /// assert_eq!(s.checked_scaled(2).unwrap().to_string(), "Sieve{2@0&!(6@0)}");
+    /// assert!(s.checked_scaled(0).is_err());
+    /// ```
+    pub fn checked_scaled(&self, k: u64) -> Result<Self, SieveError> {
+        if k == 0 {
+            return Err(SieveError::InvalidModulus { modulus: 0 });
+        }
+        Ok(Self {
+            root: self.root.scaled(k)?,
+        })
+    }
+
+    /// Return a Sieve of the negation of all values of this Sieve, inverting the Sieve about zero. This is also available with the unary `-` operator.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!(s.reflected().to_string(), "Sieve{3@0|4@3}");
+    /// assert_eq!((-&s).contains(-5), true);
+    /// ```
+    pub fn reflected(&self) -> Self {
+        let root = self
+            .root
+            .try_map(&|r: &Residual| Ok(r.reflected()))
+            .unwrap();
+        Self { root }
+    }
+
+    /// Return the Boolean states of one period of this Sieve, starting from zero. As with `compile`, a `SieveError::ExceedsBudget` is returned if the states require more than `CompiledSieve::DEFAULT_BUDGET` bytes.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert_eq!(
+    ///     s.pattern().unwrap(),
+    ///     vec![true, true, false, true, false, true, true, false, false, true, false, false]
+    /// );
+    /// assert!(xensieve::Sieve::new("1000000007@0|1000000009@0").pattern().is_err());
+    /// ```
+    pub fn pattern(&self) -> Result<Vec<bool>, SieveError> {
+        let period = self.period()?;
+        let budget = CompiledSieve::DEFAULT_BUDGET;
+        if period > budget as u64 {
+            return Err(SieveError::ExceedsBudget { period, budget });
+        }
+        Ok(self.iter_state(0..period as i128).collect())
+    }
+
+    /// Render this Sieve over the range as lines of text, with a labelled row for each Residual, drawn with `█` for contained integers and `·` otherwise, followed by a row for this Sieve. See `render_ascii_with` to select other rows, characters, or an axis.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("5@0|4@2");
+    /// assert_eq!(
+    ///     s.render_ascii(0..12),
+    ///     "5@0      █····█····█·\n4@2      ··█···█···█·\n5@0|4@2  █·█··██···█·"
+    /// );
+    /// ```
+    pub fn render_ascii(&self, range: Range<i128>) -> String {
+        self.render_ascii_with(range, &RenderOptions::default())
+    }
+
+    /// Render this Sieve over the range as lines of text, with rows, characters, and axis selected by `options`.
+    pub fn render_ascii_with(&self, range: Range<i128>, options: &RenderOptions) -> String {
+        render::render(&self.root, range, options)
+    }
+
+    /// Render this Sieve over the range as an SVG diagram, with a lane of cells for each Residual or subtree selected by `options`, followed by a lane for this Sieve.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("5@0|4@2");
+    /// let svg = s.to_svg(0..20, &xensieve::SvgOptions::default());
+    /// assert_eq!(svg.matches("<rect").count(), 3 * 20);
+    /// ```
+    pub fn to_svg(&self, range: Range<i128>, options: &SvgOptions) -> String {
+        render::render_svg(&self.root, range, options)
+    }
+
+    /// Render the expression tree of this Sieve as a Graphviz DOT digraph, with operators as internal nodes and Residuals as leaves.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|!4@1");
+    /// assert_eq!(
+    ///     s.to_dot(),
+    ///     "digraph Sieve {\n    node [fontname=\"monospace\"];\n    n0 [label=\"|\", shape=ellipse];\n    n1 [label=\"3@0\", shape=box];\n    n0 -> n1;\n    n2 [label=\"!\", shape=ellipse];\n    n3 [label=\"4@1\", shape=box];\n    n2 -> n3;\n    n0 -> n2;\n}"
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String {
+        render::render_dot(&self.root, false).expect("Rendering without annotations does not fail")
+    }
+
+    /// Render the expression tree of this Sieve as a Graphviz DOT digraph, as `to_dot`, with each node labelled with the period and density of its subtree. A `SieveError::ExceedsBudget` is returned if the period of a subtree exceeds that permitted by `compile`.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@1");
+    /// assert!(s.to_dot_annotated().unwrap().contains("period: 12\\ndensity: 0.500000"));
+    /// ```
+    pub fn to_dot_annotated(&self) -> Result<String, SieveError> {
+        render::render_dot(&self.root, true)
+    }
+
+    /// Compile this Sieve to a lookup table of one period, permitting constant-time membership tests. A `SieveError::ExceedsBudget` is returned if the table requires more than `CompiledSieve::DEFAULT_BUDGET` bytes.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// let c = s.compile().unwrap();
+    /// assert_eq!(c.iter_value(0..=12).collect::<Vec<_>>(), s.iter_value(0..=12).collect::<Vec<_>>());
+    /// ```
+    pub fn compile(&self) -> Result<CompiledSieve, SieveError> {
+        CompiledSieve::from_node(&self.root, CompiledSieve::DEFAULT_BUDGET)
+    }
+
+    /// Compile this Sieve to a lookup table of one period, returning a `SieveError::ExceedsBudget` if the table requires more than `budget` bytes.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("997@0|991@1");
+    /// assert!(s.compile_with_budget(1024).is_err());
+    /// assert!(s.compile_with_budget(1 << 20).is_ok());
+    /// ```
+    pub fn compile_with_budget(&self, budget: usize) -> Result<CompiledSieve, SieveError> {
+        CompiledSieve::from_node(&self.root, budget)
+    }
+
+    /// Return an equivalent Sieve in normal form: a union of disjoint Residuals. Where the period permits a lookup table within the budget of `compile`, the Residuals are a canonical selection derived from the table, such that equivalent Sieves have the same normal form. Beyond that budget, the expression is rewritten with Residual intersections, and unions, differences, and inversions are made disjoint by refining moduli only as needed, without evaluating the period; the rewritten Residuals are returned in ascending order. A `SieveError::ExceedsBudget` is returned if the rewritten Residuals require more than `CompiledSieve::DEFAULT_BUDGET` bytes.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("!(3@0|3@1)");
+    /// assert_eq!(s.to_normal_form().unwrap().to_string(), "Sieve{3@2}");
+    ///
+    /// let s = xensieve::Sieve::new("(6@0|4@1)&!12@0");
+    /// assert_eq!(s.to_normal_form().unwrap().to_string(), "Sieve{4@1|12@6}");
+    ///
+    /// let s = xensieve::Sieve::new("1000000007@3&!(2@0|3@1)");
+    /// assert_eq!(s.to_normal_form().unwrap().to_string(), "Sieve{6000000042@3|6000000042@4000000031}");
+    /// ```
+    pub fn to_normal_form(&self) -> Result<Self, SieveError> {
+        let budget = CompiledSieve::DEFAULT_BUDGET;
+        let residuals = match CompiledSieve::from_node(&self.root, budget) {
+            Ok(c) => c.to_residuals(),
+            Err(_) => disjoint::to_disjoint(&self.root, budget)?,
+        };
+        Ok(Self {
+            root: SieveNode::from_residuals(residuals, Residual::new(0, 0)),
+        })
+    }
+
+    /// Return `true` if the node contains no values, as decided over one period or, where the period exceeds that permitted by `compile`, by the absence of disjoint Residuals in the normal form. If neither is permitted, the error of compiling the lookup table is returned.
+    fn is_empty_node(node: &SieveNode) -> Result<bool, SieveError> {
+        let budget = CompiledSieve::DEFAULT_BUDGET;
+        match CompiledSieve::from_node(node, budget) {
+            Ok(c) => Ok(c.is_empty()),
+            Err(e) => disjoint::to_disjoint(node, budget)
+                .map(|residuals| residuals.is_empty())
+                .map_err(|_| e),
+        }
+    }
+
+    /// Return `true` if this Sieve contains no values. As this is decided over one period, or from the disjoint Residuals of the normal form, a `SieveError::ExceedsBudget` is returned if both exceed the budget of `compile`.
+    ///
+    /// ```
+    /// assert_eq!(xensieve::Sieve::new("3@0&3@1").is_empty().unwrap(), true);
+    /// assert_eq!(xensieve::Sieve::new("3@0&4@1").is_empty().unwrap(), false);
+    /// ```
+    pub fn is_empty(&self) -> Result<bool, SieveError> {
+        Self::is_empty_node(&self.root)
+    }
+
+    /// Return `true` if this Sieve contains all integers. As this is decided over one period, or from the disjoint Residuals of the normal form, a `SieveError::ExceedsBudget` is returned if both exceed the budget of `compile`.
+    ///
+    /// ```
+    /// assert_eq!(xensieve::Sieve::new("2@0|2@1").is_universal().unwrap(), true);
+    /// assert_eq!(xensieve::Sieve::new("2@0|3@1").is_universal().unwrap(), false);
+    /// ```
+    pub fn is_universal(&self) -> Result<bool, SieveError> {
+        Self::is_empty_node(&SieveNode::Inversion(Box::new(self.root.clone())))
+    }
+
+    /// Return `true` if this Sieve contains the same values as `other`. As this is decided over the combined period of both Sieves, or from the disjoint Residuals of their combination, a `SieveError::ExceedsBudget` is returned if both exceed the budget of `compile`.
+    ///
+    /// ```
+    /// let s1 = xensieve::Sieve::new("!(3@0|3@1)");
+    /// let s2 = xensieve::Sieve::new("3@2");
+    /// assert_eq!(s1.is_equivalent(&s2).unwrap(), true);
+    /// assert_eq!(s1 == s2, true);
+    /// ```
+    pub fn is_equivalent(&self, other: &Self) -> Result<bool, SieveError> {
+        Self::is_empty_node(&(self ^ other).root)
+    }
+
+    /// Return `true` if all values of this Sieve are contained in `other`. As this is decided over the combined period of both Sieves, or from the disjoint Residuals of their combination, a `SieveError::ExceedsBudget` is returned if both exceed the budget of `compile`.
+    ///
+    /// ```
+    /// let s1 = xensieve::Sieve::new("6@1");
+    /// assert_eq!(s1.is_subset(&xensieve::Sieve::new("2@1|3@0")).unwrap(), true);
+    /// assert_eq!(s1.is_subset(&xensieve::Sieve::new("4@1")).unwrap(), false);
+    /// ```
+    pub fn is_subset(&self, other: &Self) -> Result<bool, SieveError> {
+        Self::is_empty_node(&(self & &!other).root)
+    }
+
+    /// Return `true` if all values of `other` are contained in this Sieve. As this is decided over the combined period of both Sieves, or from the disjoint Residuals of their combination, a `SieveError::ExceedsBudget` is returned if both exceed the budget of `compile`.
+    ///
+    /// ```
+    /// let s1 = xensieve::Sieve::new("2@1|3@0");
+    /// assert_eq!(s1.is_superset(&xensieve::Sieve::new("6@1")).unwrap(), true);
+    /// ```
+    pub fn is_superset(&self, other: &Self) -> Result<bool, SieveError> {
+        other.is_subset(self)
+    }
+
+    /// Return `true` if this Sieve and `other` have no values in common. As this is decided over the combined period of both Sieves, or from the disjoint Residuals of their combination, a `SieveError::ExceedsBudget` is returned if both exceed the budget of `compile`.
+    ///
+    /// ```
+    /// let s1 = xensieve::Sieve::new("4@0|4@1");
+    /// assert_eq!(s1.is_disjoint(&xensieve::Sieve::new("2@0&!4@0")).unwrap(), true);
+    /// assert_eq!(s1.is_disjoint(&xensieve::Sieve::new("3@0")).unwrap(), false);
+    /// ```
+    pub fn is_disjoint(&self, other: &Self) -> Result<bool, SieveError> {
+        Self::is_empty_node(&(self & other).root)
+    }
+
+    /// Iterate, in ascending order, all values of the Sieve greater than or equal to `start`. Rather than testing each integer, values are found directly from the members of one period or, where the period exceeds that permitted by `compile`, by stepping each of the disjoint Residuals of the normal form. A `SieveError::ExceedsBudget` is returned if the disjoint Residuals also exceed that budget.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.iter_from(-4).unwrap().take(5).collect::<Vec<_>>(), vec![-4, -3, 0, 3, 4]);
+    ///
+    /// let s = xensieve::Sieve::new("1000000007@3");
+    /// assert_eq!(s.iter_from(0).unwrap().take(2).collect::<Vec<_>>(), vec![3, 1000000010]);
+    /// ```
+    pub fn iter_from(&self, start: i128) -> Result<IterMembers, SieveError> {
+        Ok(IterMembers::new(
+            members::Members::from_node(&self.root)?,
+            start,
+            None,
+        ))
+    }
+
+    /// Iterate, in ascending order, all values of the Sieve within the range, taking time proportional to the number of values rather than the size of the range.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("1000@0");
+    /// assert_eq!(s.values_in(0..10_000_000_000).unwrap().count(), 10_000_000);
+    /// ```
+    pub fn values_in(&self, range: Range<i128>) -> Result<IterMembers, SieveError> {
+        Ok(IterMembers::new(
+            members::Members::from_node(&self.root)?,
+            range.start,
+            Some(range.end),
+        ))
+    }
+
+    /// Return the smallest value of the Sieve greater than `value`, or `None` if there is no such value. Like all methods that navigate members, the value is found from the members of one period or, where the period exceeds that permitted by `compile`, from the disjoint Residuals of the normal form; a `SieveError::ExceedsBudget` is returned if the disjoint Residuals also exceed that budget.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@4|12@7");
+    /// assert_eq!(s.next_after(61).unwrap(), Some(64));
+    /// ```
+    pub fn next_after(&self, value: i128) -> Result<Option<i128>, SieveError> {
+        let members = members::Members::from_node(&self.root)?;
+        Ok(value.checked_add(1).and_then(|v| members.first_from(v)))
+    }
 
-impl Not for &Sieve {
-    type Output = Sieve;
+    /// Return the largest value of the Sieve less than `value`, or `None` if there is no such value.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("12@0|12@4|12@7");
+    /// assert_eq!(s.prev_before(61).unwrap(), Some(60));
+    /// ```
+    pub fn prev_before(&self, value: i128) -> Result<Option<i128>, SieveError> {
+        Ok(members::Members::from_node(&self.root)?.prev_before(value))
+    }
 
-    fn not(self) -> Self::Output {
-        Sieve {
-            root: SieveNode::Inversion(Box::new(self.root.clone())),
-        }
+    /// Return the value of the Sieve `n` members from `start`: for `n` of zero, the first value greater than or equal to `start`; for negative `n`, values less than `start`, where -1 is the largest value less than `start`.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("4@0|6@1");
+    /// assert_eq!(s.nth_from(0, 500).unwrap(), Some(1200));
+    /// assert_eq!(s.nth_from(0, -3).unwrap(), Some(-8));
+    ///
+    /// let s = xensieve::Sieve::new("1000000007@3|1000000007@5");
+    /// assert_eq!(s.nth_from(0, 5).unwrap(), Some(2000000019));
+    /// ```
+    pub fn nth_from(&self, start: i128, n: i128) -> Result<Option<i128>, SieveError> {
+        Ok(members::Members::from_node(&self.root)?.nth_from(start, n))
     }
-}
 
-impl fmt::Display for Sieve {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Sieve{{{}}}", self.root)
+    /// Return the number of values of the Sieve within the range, computed from the members of one period or, where the period exceeds that permitted by `compile`, summed arithmetically from the disjoint Residuals of the normal form. A `SieveError::ExceedsBudget` is returned if the disjoint Residuals also exceed that budget.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("(5@0|4@2)&!30@10");
+    /// assert_eq!(s.count_in(0..50).unwrap(), 18);
+    /// assert_eq!(s.count_in(0..6_000_000).unwrap(), 2_200_000);
+    ///
+    /// let s = xensieve::Sieve::new("1000000007@3");
+    /// assert_eq!(s.count_in(0..1_000_000_000_000).unwrap(), 1000);
+    /// ```
+    pub fn count_in(&self, range: Range<i128>) -> Result<u128, SieveError> {
+        Ok(members::Members::from_node(&self.root)?.count_in(range))
     }
-}
 
-impl Sieve {
-    /// Construct a Xenakis Sieve from a string representation.
+    /// Return the rank of the value: the index of the first member greater than or equal to `value`, where the first member greater than or equal to zero has index zero. The rank relative to another origin is the difference of the ranks.
     ///
     /// ```
-    /// let s = xensieve::Sieve::new("3@0|5@1");
-    /// assert_eq!(s.iter_value(0..15).collect::<Vec<_>>(), vec![0, 1, 3, 6, 9, 11, 12])
-    /// ````
-    pub fn new(value: &str) -> Self {
-        let mut stack: Vec<Self> = Vec::new();
-        for token in parser::infix_to_postfix(value).expect("Parsing failure") {
-            match token.as_str() {
-                "!" => {
-                    let s = stack.pop().expect("Invalid syntax: missing operand");
-                    stack.push(!s);
-                }
-                "&" => {
-                    let right = stack.pop().expect("Invalid syntax: missing operand");
-                    let left = stack.pop().expect("Invalid syntax: missing operand");
-                    stack.push(left & right);
-                }
-                "^" => {
-                    let right = stack.pop().expect("Invalid syntax: missing operand");
-                    let left = stack.pop().expect("Invalid syntax: missing operand");
-                    stack.push(left ^ right);
-                }
-                "|" => {
-                    let right = stack.pop().expect("Invalid syntax: missing operand");
-                    let left = stack.pop().expect("Invalid syntax: missing operand");
-                    stack.push(left | right);
-                }
-                operand => {
-                    let (m, s) = parser::residual_to_ints(operand)
-                        .expect("Invalid syntax: cannot parse Residual");
-                    let r = Residual::new(m, s);
-                    let s = Self {
-                        root: SieveNode::Unit(r),
-                    };
-                    stack.push(s);
-                }
-            }
-        }
-        stack.pop().expect("Invalid syntax: no result")
+    /// let s = xensieve::Sieve::new("(5@0|4@2)&!30@10");
+    /// assert_eq!(s.rank(46).unwrap(), 17);
+    /// assert_eq!(s.rank(46).unwrap() - s.rank(30).unwrap(), 6);
+    /// ```
+    pub fn rank(&self, value: i128) -> Result<i128, SieveError> {
+        Ok(members::Members::from_node(&self.root)?.rank(value))
     }
 
-    /// Return `true` if the value is contained with this Sieve.
+    /// Return the member at the rank `k`, or `None` if there are no members or the member exceeds `i128`.
     ///
     /// ```
-    /// let s = xensieve::Sieve::new("3@0 & 5@0");
-    /// assert_eq!(s.contains(15), true);
-    /// assert_eq!(s.contains(16), false);
-    /// assert_eq!(s.contains(30), true);
+    /// let s = xensieve::Sieve::new("(5@0|4@2)&!30@10");
+    /// assert_eq!(s.select(17).unwrap(), Some(46));
+    ///
+    /// let s = xensieve::Sieve::new("1000000007@3");
+    /// assert_eq!(s.select(s.rank(-5).unwrap()).unwrap(), Some(3));
     /// ```
-    pub fn contains(&self, value: i128) -> bool {
-        self.root.contains(value)
+    pub fn select(&self, k: i128) -> Result<Option<i128>, SieveError> {
+        Ok(members::Members::from_node(&self.root)?.select(k))
+    }
+
+    /// Return a bidirectional `SieveCursor` positioned at `start`.
+    ///
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// let mut c = s.cursor(5).unwrap();
+    /// assert_eq!(c.next(), Some(6));
+    /// assert_eq!(c.prev(), Some(6));
+    /// assert_eq!(c.prev(), Some(4));
+    /// ```
+    pub fn cursor(&self, start: i128) -> Result<SieveCursor, SieveError> {
+        Ok(SieveCursor::new(
+            members::Members::from_node(&self.root)?,
+            start,
+        ))
     }
 
     /// For the iterator provided as an input, iterate the subset of values that are contained within the sieve.
@@ -298,10 +1307,23 @@ impl Sieve {
         &self,
         iterator: impl Iterator<Item = i128>,
     ) -> IterValue<impl Iterator<Item = i128>> {
+        self.iter_value_int(iterator)
+    }
+
+    /// For the iterator provided as an input, of any primitive integer type, iterate the subset of values that are contained within the sieve, as the same type. See `SieveInt`.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.iter_value_int(0u8..=12).collect::<Vec<u8>>(), vec![0, 3, 4, 6, 8, 9, 12])
+    /// ````
+    pub fn iter_value_int<I>(&self, iterator: I) -> IterValue<I>
+    where
+        I: Iterator,
+        I::Item: SieveInt,
+    {
         // NOTE: do not want to clone self here...
         IterValue {
             iterator,
-            sieve_node: self.root.clone(),
+            membership: Membership::Node(self.root.clone()),
         }
     }
 
@@ -314,9 +1336,22 @@ impl Sieve {
         &self,
         iterator: impl Iterator<Item = i128>,
     ) -> IterState<impl Iterator<Item = i128>> {
+        self.iter_state_int(iterator)
+    }
+
+    /// For the iterator provided as an input, of any primitive integer type, iterate the Boolean status of contained. See `SieveInt`.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.iter_state_int(0u16..=4).collect::<Vec<_>>(), vec![true, false, false, true, true])
+    /// ````
+    pub fn iter_state_int<I>(&self, iterator: I) -> IterState<I>
+    where
+        I: Iterator,
+        I::Item: SieveInt,
+    {
         IterState {
             iterator,
-            sieve_node: self.root.clone(),
+            membership: Membership::Node(self.root.clone()),
         }
     }
 
@@ -329,9 +1364,25 @@ impl Sieve {
         &self,
         iterator: impl Iterator<Item = i128>,
     ) -> IterInterval<impl Iterator<Item = i128>> {
+        self.iter_interval_int(iterator)
+    }
+
+    /// For the iterator provided as an input, of any primitive integer type, iterate over integer intervals between values in the sieve, as the same type. Iteration ends at the first interval that cannot be represented by the type. See `SieveInt`.
+    /// ```
+    /// let s = xensieve::Sieve::new("3@0|4@0");
+    /// assert_eq!(s.iter_interval_int(0i8..=12).collect::<Vec<i8>>(), vec![3, 1, 2, 2, 1, 3]);
+    ///
+    /// let s = xensieve::Sieve::new("200@100|200@101");
+    /// assert_eq!(s.iter_interval_int(-100i8..=100).collect::<Vec<i8>>(), vec![1]);
+    /// ````
+    pub fn iter_interval_int<I>(&self, iterator: I) -> IterInterval<I>
+    where
+        I: Iterator,
+        I::Item: SieveInt,
+    {
         IterInterval {
             iterator,
-            sieve_node: self.root.clone(),
+            membership: Membership::Node(self.root.clone()),
             last: PositionLast::Init,
         }
     }
@@ -339,6 +1390,25 @@ impl Sieve {
 
 //------------------------------------------------------------------------------
 
+/// The source of membership tests for the iterators, either the graph of Residuals or a compiled lookup table.
+#[derive(Clone, Debug)]
+enum Membership {
+    Node(SieveNode),
+    Compiled(CompiledSieve),
+}
+
+impl Membership {
+    #[inline(always)]
+    fn contains<T: SieveInt>(&self, value: T) -> bool {
+        match self {
+            Membership::Node(node) => node.contains(value),
+            Membership::Compiled(compiled) => compiled.contains_int(value),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
 /// The iterator returned by `iter_value`.
 /// ```
 /// let s = xensieve::Sieve::new("3@0|4@0");
@@ -348,22 +1418,24 @@ impl Sieve {
 /// ```
 pub struct IterValue<I>
 where
-    I: Iterator<Item = i128>,
+    I: Iterator,
+    I::Item: SieveInt,
 {
     iterator: I,
-    sieve_node: SieveNode,
+    membership: Membership,
 }
 
 impl<I> Iterator for IterValue<I>
 where
-    I: Iterator<Item = i128>,
+    I: Iterator,
+    I::Item: SieveInt,
 {
-    type Item = i128;
+    type Item = I::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.iterator
             .by_ref()
-            .find(|&p| self.sieve_node.contains(p))
+            .find(|&p| self.membership.contains(p))
     }
 }
 
@@ -380,21 +1452,23 @@ where
 /// ```
 pub struct IterState<I>
 where
-    I: Iterator<Item = i128>,
+    I: Iterator,
+    I::Item: SieveInt,
 {
     iterator: I,
-    sieve_node: SieveNode,
+    membership: Membership,
 }
 
 impl<I> Iterator for IterState<I>
 where
-    I: Iterator<Item = i128>,
+    I: Iterator,
+    I::Item: SieveInt,
 {
     type Item = bool;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.iterator.next() {
-            Some(p) => Some(self.sieve_node.contains(p)),
+            Some(p) => Some(self.membership.contains(p)),
             None => None,
         }
     }
@@ -402,9 +1476,9 @@ where
 
 //------------------------------------------------------------------------------
 
-enum PositionLast {
+enum PositionLast<T> {
     Init,
-    Value(i128),
+    Value(T),
 }
 
 /// The iterator returned by `iter_interval`.
@@ -417,23 +1491,25 @@ enum PositionLast {
 /// ```
 pub struct IterInterval<I>
 where
-    I: Iterator<Item = i128>,
+    I: Iterator,
+    I::Item: SieveInt,
 {
     iterator: I,
-    sieve_node: SieveNode,
-    last: PositionLast,
+    membership: Membership,
+    last: PositionLast<I::Item>,
 }
 
 impl<I> Iterator for IterInterval<I>
 where
-    I: Iterator<Item = i128>,
+    I: Iterator,
+    I::Item: SieveInt,
 {
-    type Item = i128;
+    type Item = I::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
         for p in self.iterator.by_ref() {
             // while let Some(p) = self.iterator.next() {
-            if self.sieve_node.contains(p) {
+            if self.membership.contains(p) {
                 match self.last {
                     PositionLast::Init => {
                         // drop the first value
@@ -441,7 +1517,8 @@ where
                         continue;
                     }
                     PositionLast::Value(last) => {
-                        let post = p - last;
+                        // end, rather than wrap, if the interval exceeds the type
+                        let post = p.interval(last)?;
                         self.last = PositionLast::Value(p);
                         return Some(post);
                     }
@@ -455,6 +1532,7 @@ where
 //------------------------------------------------------------------------------
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
 mod tests {
     use super::*;
 
@@ -580,6 +1658,105 @@ mod tests {
         assert_eq!((r1 & r2).to_string(), "0@0");
     }
 
+    #[test]
+    fn test_residual_intersection_a() {
+        let r1 = Residual::new(10, 3);
+        let r2 = Residual::new(4, 1);
+        let r3 = r1.intersection(&r2).unwrap();
+        assert_eq!(r3, Residual::new(20, 13));
+        assert!(r3.is_subset_of(&r1));
+        assert!(r3.is_subset_of(&r2));
+        assert_eq!(Residual::new(3, 2).intersection(&Residual::new(3, 1)), None);
+        assert_eq!(Residual::new(0, 0).intersection(&Residual::new(3, 1)), None);
+        let r4 = Residual::new(45, 11)
+            .intersection(&Residual::new(40, 1))
+            .unwrap();
+        assert_eq!(r4, Residual::new(360, 281));
+    }
+
+    #[test]
+    fn test_residual_checked_intersection_a() {
+        let r1 = Residual::new(u64::MAX, 3);
+        let r2 = Residual::new(u64::MAX - 1, 3);
+        assert_eq!(r1.checked_intersection(&r2), Err(SieveError::Overflow));
+        assert_eq!(
+            r1.checked_intersection(&Residual::new(3, 0)),
+            Ok(Some(Residual::new(u64::MAX, 3)))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_residual_bitand_e() {
+        let _r = Residual::new(u64::MAX, 3) & Residual::new(u64::MAX - 1, 3);
+    }
+
+    #[test]
+    fn test_residual_is_disjoint_a() {
+        for (m1, s1, m2, s2) in [
+            (4, 0, 6, 1),
+            (4, 1, 6, 3),
+            (5, 2, 7, 3),
+            (3, 0, 0, 0),
+            (1, 0, 9, 8),
+        ] {
+            let r1 = Residual::new(m1, s1);
+            let r2 = Residual::new(m2, s2);
+            let expected = (-100..100).all(|v| !(r1.contains(v) && r2.contains(v)));
+            assert_eq!(r1.is_disjoint(&r2), expected);
+            assert_eq!(r2.is_disjoint(&r1), expected);
+        }
+    }
+
+    #[test]
+    fn test_residual_is_subset_of_a() {
+        let r1 = Residual::new(0, 0);
+        let r2 = Residual::new(3, 1);
+        assert!(r1.is_subset_of(&r2));
+        assert!(!r2.is_subset_of(&r1));
+        assert!(r2.is_subset_of(&r2));
+        assert!(r2.is_subset_of(&Residual::new(1, 0)));
+        assert!(!Residual::new(6, 1).is_subset_of(&Residual::new(4, 1)));
+    }
+
+    #[test]
+    fn test_residual_next_after_a() {
+        let r1 = Residual::new(5, 3);
+        assert_eq!(r1.next_after(-10), Some(-7));
+        assert_eq!(r1.next_after(-7), Some(-2));
+        assert_eq!(r1.next_after(2), Some(3));
+        assert_eq!(r1.next_after(3), Some(8));
+        assert_eq!(r1.next_after(i128::MAX - 1), None);
+        assert_eq!(Residual::new(0, 0).next_after(0), None);
+    }
+
+    #[test]
+    fn test_residual_try_new_a() {
+        assert_eq!(Residual::try_new(5, -1).unwrap(), Residual::new(5, 4));
+        assert_eq!(Residual::try_new(5, i128::MIN).unwrap().shift(), 2);
+        assert_eq!(Residual::try_new(0, -1).unwrap(), Residual::new(0, 0));
+        assert_eq!(
+            Residual::try_new(1 << 64, 0).unwrap_err(),
+            SieveError::InvalidModulus { modulus: 1 << 64 }
+        );
+    }
+
+    #[test]
+    fn test_residual_from_str_a() {
+        assert_eq!("7@9".parse::<Residual>().unwrap(), Residual::new(7, 2));
+        assert!("7@".parse::<Residual>().is_err());
+    }
+
+    #[test]
+    fn test_residual_hash_a() {
+        use std::collections::HashSet;
+        let mut post: HashSet<Residual> = HashSet::new();
+        post.insert(Residual::new(3, 1));
+        post.insert(Residual::new(3, 4));
+        post.insert(Residual::new(3, 2));
+        assert_eq!(post.len(), 2);
+    }
+
     //--------------------------------------------------------------------------
 
     #[test]
@@ -596,6 +1773,13 @@ mod tests {
         assert_eq!(r1.contains(5), false);
     }
 
+    #[test]
+    fn test_residual_contains_d() {
+        let r1 = Residual::new(7, 3);
+        assert_eq!(r1.contains(i128::MIN), i128::MIN.rem_euclid(7) == 3);
+        assert_eq!(r1.contains(i128::MAX), i128::MAX.rem_euclid(7) == 3);
+    }
+
     #[test]
     fn test_residual_contains_b() {
         let r1 = Residual::new(0, 0);
@@ -646,6 +1830,356 @@ mod tests {
         assert_eq!(s1.to_string(), "Sieve{0@0}");
     }
 
+    #[test]
+    fn test_sieve_try_new_a() {
+        assert_eq!(
+            Sieve::try_new("3@1 + 4@0").unwrap_err(),
+            SieveError::UnsupportedCharacter {
+                character: '+',
+                span: Span::new(4, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_sieve_try_new_h() {
+        assert_eq!(
+            Sieve::try_new("10@0 ^ -10@9").unwrap_err(),
+            SieveError::MissingOperand {
+                operator: '^',
+                span: Span::new(5, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_sieve_new_negative_shift_a() {
+        let s1 = Sieve::new("3@-1|4@-5");
+        assert_eq!(s1.to_string(), "Sieve{3@2|4@3}");
+        assert_eq!(s1, Sieve::new("3@2|4@3"));
+        let s2 = Sieve::new("!3@-1-4@-1");
+        assert_eq!(s2.to_string(), "Sieve{!(3@2)-4@3}");
+        assert_eq!("3@-4".parse::<Residual>().unwrap(), Residual::new(3, 2));
+    }
+
+    #[test]
+    fn test_sieve_difference_a() {
+        let s1 = Sieve::new("3@0|4@0 - 12@0");
+        assert_eq!(
+            s1.iter_value(0..=24).collect::<Vec<_>>(),
+            vec![0, 3, 4, 6, 8, 9, 12, 15, 16, 18, 20, 21, 24]
+        );
+        assert_eq!(s1.to_string(), "Sieve{3@0|4@0-12@0}");
+        let s2 = Sieve::new("(3@0|4@0) - 12@0");
+        assert_eq!(s2.to_string(), "Sieve{(3@0|4@0)-12@0}");
+        assert!(s2.is_equivalent(&Sieve::new("(3@0|4@0)&!12@0")).unwrap());
+    }
+
+    #[test]
+    fn test_sieve_difference_b() {
+        // difference is left-associative
+        let s1 = Sieve::new("1@0 - 2@0 - 3@0");
+        let s2 = Sieve::new("1@0 - (2@0 - 3@0)");
+        assert_eq!(s1.to_string(), "Sieve{1@0-2@0-3@0}");
+        assert_eq!(s2.to_string(), "Sieve{1@0-(2@0-3@0)}");
+        assert_eq!(s1.iter_value(0..6).collect::<Vec<_>>(), vec![1, 5]);
+        assert_eq!(s2.iter_value(0..6).collect::<Vec<_>>(), vec![0, 1, 3, 5]);
+        assert_eq!(Sieve::new(&s2.to_string()), s2);
+    }
+
+    #[test]
+    fn test_sieve_difference_c() {
+        let s1 = Sieve::new("5@0") - Sieve::new("10@0");
+        let s2 = &Sieve::new("5@0") - &Sieve::new("10@0");
+        assert_eq!(s1.to_string(), "Sieve{5@0-10@0}");
+        assert_eq!(
+            s1.compile().unwrap().iter_value(0..30).collect::<Vec<_>>(),
+            vec![5, 15, 25]
+        );
+        assert_eq!(s2, Sieve::new("10@5"));
+        assert_eq!((s1 * 2).to_string(), "Sieve{10@0-20@0}");
+    }
+
+    #[test]
+    fn test_sieve_try_new_b() {
+        assert_eq!(
+            Sieve::try_new("3@1 | 4@").unwrap_err(),
+            SieveError::MalformedResidual {
+                residual: String::from("4@"),
+                span: Span::new(6, 2)
+            }
+        );
+    }
+
+    #[test]
+    fn test_sieve_try_new_c() {
+        assert_eq!(
+            Sieve::try_new("!").unwrap_err(),
+            SieveError::MissingOperand {
+                operator: '!',
+                span: Span::new(0, 1)
+            }
+        );
+        assert_eq!(
+            Sieve::try_new("^ 3@1").unwrap_err(),
+            SieveError::MissingOperand {
+                operator: '^',
+                span: Span::new(0, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_sieve_try_new_d() {
+        assert_eq!(
+            Sieve::try_new("(3@1 | 4@0").unwrap_err(),
+            SieveError::UnbalancedParenthesis {
+                parenthesis: '(',
+                span: Span::new(0, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_sieve_try_new_e() {
+        assert_eq!(
+            Sieve::try_new("3@1 (4@0)").unwrap_err(),
+            SieveError::TrailingOperands {
+                span: Span::new(5, 3)
+            }
+        );
+    }
+
+    #[test]
+    fn test_sieve_try_new_i() {
+        // whitespace is ignored, including within a Residual
+        let s1 = Sieve::try_new("3 @ 0 | 4@ -1").unwrap();
+        assert_eq!(s1.to_string(), "Sieve{3@0|4@3}");
+    }
+
+    #[test]
+    fn test_sieve_try_new_f() {
+        assert_eq!(
+            Sieve::try_new("").unwrap_err(),
+            SieveError::EmptyExpression {
+                span: Span::new(0, 0)
+            }
+        );
+        assert_eq!(
+            Sieve::try_new(" ( ) ").unwrap_err(),
+            SieveError::EmptyExpression {
+                span: Span::new(0, 5)
+            }
+        );
+    }
+
+    #[test]
+    fn test_sieve_try_new_g() {
+        // the span of trailing operands covers the whole sub-expression
+        let e = Sieve::try_new("3@1 (4@0 | 5@2)").unwrap_err();
+        assert_eq!(e.span(), Some(Span::new(5, 9)));
+        assert_eq!(
+            e.render("3@1 (4@0 | 5@2)"),
+            "Found operands without an operator (at 5).\n3@1 (4@0 | 5@2)\n     ^^^^^^^^^"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid syntax")]
+    fn test_sieve_new_e() {
+        Sieve::new("3@1 |");
+    }
+
+    #[test]
+    fn test_residual_shifted_a() {
+        let r1 = Residual::new(7, 3);
+        for n in [-20, -7, -1, 0, 1, 6, 7, 100, i128::MIN, i128::MAX] {
+            let r2 = r1.shifted(n);
+            for v in -30i128..30 {
+                // v is in the shifted Residual if v - n is in the original
+                let x = (v.rem_euclid(7) - n.rem_euclid(7)).rem_euclid(7);
+                assert_eq!(r2.contains(v), r1.contains(x));
+            }
+        }
+        assert_eq!(Residual::new(0, 0).shifted(3), Residual::new(0, 0));
+    }
+
+    #[test]
+    fn test_residual_reflected_a() {
+        let r1 = Residual::new(5, 2);
+        let r2 = r1.reflected();
+        for v in -30..30 {
+            assert_eq!(r2.contains(v), r1.contains(-v));
+        }
+        assert_eq!(Residual::new(5, 0).reflected(), Residual::new(5, 0));
+        assert_eq!(Residual::new(0, 0).reflected(), Residual::new(0, 0));
+    }
+
+    #[test]
+    fn test_residual_checked_scaled_a() {
+        assert_eq!(
+            Residual::new(6, 5).checked_scaled(3).unwrap(),
+            Residual::new(18, 15)
+        );
+        assert_eq!(
+            Residual::new(u64::MAX, 5).checked_scaled(2),
+            Err(SieveError::Overflow)
+        );
+        assert_eq!(
+            Residual::new(6, 5).checked_scaled(0),
+            Err(SieveError::InvalidModulus { modulus: 0 })
+        );
+    }
+
+    #[test]
+    fn test_sieve_shifted_a() {
+        let s1 = Sieve::new("(5@0|4@2)&!30@10");
+        let s2 = s1.shifted(7);
+        for v in -100..100 {
+            assert_eq!(s2.contains(v), s1.contains(v - 7));
+        }
+        let s3 = &s1 - 7;
+        for v in -100..100 {
+            assert_eq!(s3.contains(v), s1.contains(v + 7));
+        }
+        let s4 = s1.clone() - i128::MIN;
+        for v in 0..100 {
+            assert_eq!(s4.contains(v), s1.contains(v + i128::MIN));
+        }
+    }
+
+    #[test]
+    fn test_sieve_scaled_a() {
+        let s1 = Sieve::new("(5@0|4@2)&!30@10 ^ !3@1");
+        let s2 = &s1 * 3;
+        for v in -300..300 {
+            let expected = v % 3 == 0 && s1.contains(v / 3);
+            assert_eq!(s2.contains(v), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sieve_scaled_b() {
+        let _s = Sieve::new("3@1").scaled(0);
+    }
+
+    #[test]
+    fn test_sieve_reflected_a() {
+        let s1 = Sieve::new("(5@1|4@3)&!(30@11)");
+        let s2 = -s1.clone();
+        for v in -100..100 {
+            assert_eq!(s2.contains(v), s1.contains(-v));
+        }
+        assert_eq!(s2.reflected().to_string(), s1.to_string());
+    }
+
+    #[test]
+    fn test_sieve_is_equivalent_a() {
+        let s1 = Sieve::new("(3@0|4@1)&!6@3");
+        let s2 = s1.to_normal_form().unwrap();
+        assert!(s1.is_equivalent(&s2).unwrap());
+        assert!(!s1.is_equivalent(&Sieve::new("3@0|4@1")).unwrap());
+        assert_eq!(s1, s2);
+        assert_ne!(s1, Sieve::new("3@0"));
+    }
+
+    #[test]
+    fn test_sieve_is_equivalent_b() {
+        // different periods, same values
+        let s1 = Sieve::new("2@0");
+        let s2 = Sieve::new("6@0|6@2|6@4");
+        assert!(s1.is_equivalent(&s2).unwrap());
+        assert!(s1.is_subset(&s2).unwrap());
+        assert!(s1.is_superset(&s2).unwrap());
+    }
+
+    #[test]
+    fn test_sieve_is_equivalent_c() {
+        let s1 = Sieve::new("997@0");
+        let s2 = Sieve::new("991@0|983@0|977@0");
+        assert!(matches!(
+            s1.is_equivalent(&s2),
+            Err(SieveError::ExceedsBudget { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sieve_is_empty_a() {
+        assert!(Sieve::new("0@0").is_empty().unwrap());
+        assert!(Sieve::new("!1@0").is_empty().unwrap());
+        assert!(!Sieve::new("1@0").is_empty().unwrap());
+        assert!(Sieve::new("1@0").is_universal().unwrap());
+        assert!(Sieve::new("!0@0").is_universal().unwrap());
+        assert!(!Sieve::new("0@0").is_universal().unwrap());
+    }
+
+    #[test]
+    fn test_sieve_is_disjoint_a() {
+        let s1 = Sieve::new("5@0|5@1");
+        assert!(s1.is_disjoint(&!&s1).unwrap());
+        assert!(s1.is_disjoint(&Sieve::new("0@0")).unwrap());
+        assert!(!s1.is_disjoint(&Sieve::new("7@6")).unwrap());
+    }
+
+    #[test]
+    fn test_sieve_hash_a() {
+        use std::collections::HashSet;
+        let mut post: HashSet<Sieve> = HashSet::new();
+        post.insert(Sieve::new("!(3@0|3@1)"));
+        post.insert(Sieve::new("3@2"));
+        post.insert(Sieve::new("6@2|6@5"));
+        post.insert(Sieve::new("3@1"));
+        assert_eq!(post.len(), 2);
+    }
+
+    #[test]
+    fn test_sieve_hash_b() {
+        use std::collections::HashSet;
+        // beyond the budget, Sieves are hashed without a period, and compared by their disjoint Residuals
+        let mut post: HashSet<Sieve> = HashSet::new();
+        post.insert(Sieve::new("1000000007@3|1000000007@5"));
+        post.insert(Sieve::new("1000000007@5|1000000007@3"));
+        post.insert(Sieve::new("1000000007@3|2000000014@5"));
+        assert_eq!(post.len(), 2);
+        assert!(Sieve::new("1000000007@3-1000000007@3").is_empty().unwrap());
+    }
+
+    #[test]
+    fn test_sieve_hash_c() {
+        use std::collections::hash_map::DefaultHasher;
+        let hash = |s: &str| {
+            let mut h = DefaultHasher::new();
+            Sieve::new(s).hash(&mut h);
+            h.finish()
+        };
+        // within the budget, Sieves that agree over `0..64` are distinguished by their periods
+        assert_ne!(hash("100@1"), hash("200@1"));
+        assert_ne!(hash("100@1"), hash("100@1|100@70"));
+        assert_eq!(hash("3@2"), hash("6@2|6@5"));
+        assert_eq!(hash("!(3@0|3@1)"), hash("3@2"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid comparison")]
+    fn test_sieve_eq_a() {
+        let s1 = Sieve::new("1000000007@0|1000000009@0");
+        let _ = s1 == s1;
+    }
+
+    #[test]
+    fn test_sieve_contains_generic_a() {
+        let s1 = Sieve::new("7@3|!2@0");
+        for v in i8::MIN..=i8::MAX {
+            assert_eq!(s1.contains_int(v), s1.contains(v as i128));
+        }
+        for v in 0..=u16::MAX {
+            assert_eq!(s1.contains_int(v), s1.contains(v as i128));
+        }
+        assert_eq!(s1.contains_int(u128::MAX), true);
+        assert_eq!(s1.contains(i128::MIN), i128::MIN.rem_euclid(7) == 3);
+    }
+
     #[test]
     fn test_sieve_contains_a() {
         let r1 = Residual::new(3, 0);
@@ -675,6 +2209,53 @@ mod tests {
 
     //--------------------------------------------------------------------------
 
+    #[test]
+    fn test_sieve_period_a() {
+        assert_eq!(Sieve::new("3@1").period().unwrap(), 3);
+        assert_eq!(Sieve::new("0@0").period().unwrap(), 1);
+        assert_eq!(Sieve::new("!0@0").period().unwrap(), 1);
+        assert_eq!(Sieve::new("3@1|0@0").period().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_sieve_period_b() {
+        assert_eq!(Sieve::new("4@1|6@0").period().unwrap(), 12);
+        assert_eq!(Sieve::new("!(4@1&6@0)^5@2").period().unwrap(), 60);
+        assert_eq!(Sieve::new("10@1|20@2|40@3").period().unwrap(), 40);
+    }
+
+    #[test]
+    fn test_sieve_period_c() {
+        let s1 = Sieve::new("18446744073709551557@0|18446744073709551533@1");
+        assert_eq!(s1.period().unwrap_err(), SieveError::Overflow);
+        assert_eq!(s1.pattern().unwrap_err(), SieveError::Overflow);
+    }
+
+    #[test]
+    fn test_sieve_pattern_a() {
+        assert_eq!(Sieve::new("0@0").pattern().unwrap(), vec![false]);
+        assert_eq!(Sieve::new("!0@0").pattern().unwrap(), vec![true]);
+        assert_eq!(
+            Sieve::new("3@2").pattern().unwrap(),
+            vec![false, false, true]
+        );
+    }
+
+    #[test]
+    fn test_sieve_pattern_b() {
+        assert_eq!(
+            Sieve::new("1000000007@0|1000000009@0")
+                .pattern()
+                .unwrap_err(),
+            SieveError::ExceedsBudget {
+                period: 1000000016000000063,
+                budget: CompiledSieve::DEFAULT_BUDGET
+            }
+        );
+    }
+
+    //--------------------------------------------------------------------------
+
     #[test]
     fn test_sieve_operators_a() {
         let s1 = Sieve::new("3@1");
@@ -711,6 +2292,29 @@ mod tests {
         assert_eq!(s3.to_string(), "Sieve{3@1^4@0}");
     }
 
+    #[test]
+    fn test_sieve_operators_f() {
+        let s1 = Sieve::new("3@1|4@0");
+        let s2 = Sieve::new("5@0");
+        let s3 = &s1 & &s2;
+        assert_eq!(s3.to_string(), "Sieve{(3@1|4@0)&5@0}");
+
+        let s4 = &s2 & &s1;
+        assert_eq!(s4.to_string(), "Sieve{5@0&(3@1|4@0)}");
+
+        let s5 = &s2 | &s1;
+        assert_eq!(s5.to_string(), "Sieve{5@0|(3@1|4@0)}");
+    }
+
+    #[test]
+    fn test_sieve_operators_g() {
+        let s1 = Sieve::new("3@1^4@0");
+        let s2 = Sieve::new("5@0&7@2");
+        assert_eq!((&s1 | &s2).to_string(), "Sieve{3@1^4@0|5@0&7@2}");
+        assert_eq!((&s1 & &s2).to_string(), "Sieve{(3@1^4@0)&(5@0&7@2)}");
+        assert_eq!((&s2 ^ &s1).to_string(), "Sieve{5@0&7@2^(3@1^4@0)}");
+    }
+
     #[test]
     fn test_sieve_operators_e() {
         let s1 = Sieve::new("3@1");
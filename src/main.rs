@@ -1,5 +1,584 @@
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::ops::Range;
+use std::process;
+
+use xensieve::RenderOptions;
+use xensieve::RenderRows;
+use xensieve::Sieve;
+use xensieve::SieveError;
+use xensieve::SvgOptions;
+
+mod repl;
+
+const USAGE: &str = "\
+Usage: xensieve <COMMAND> <EXPRESSION> [OPTIONS] [INTEGERS...]
+       xensieve repl [OPTIONS]
+
+Commands:
+  values      Print the integers within the range that are contained in the Sieve
+  states      Print, for each integer within the range, the Boolean state of the Sieve
+  intervals   Print the intervals between sequential integers contained in the Sieve
+  contains    Print, for each integer given as arguments or read from stdin, if it is contained
+  period      Print the period of the Sieve
+  normalize   Print the Sieve as a union of disjoint Residuals
+  explain     Print the period, density, and normal form of the Sieve
+  render      Draw a row for each Residual and a row for the Sieve over the range
+  svg         Draw the rows of render as an SVG diagram
+  dot         Print the expression tree of the Sieve as a Graphviz DOT digraph
+  repl        Start an interactive session to bind, combine, and display Sieves
+
+Options:
+  -r, --range <START..END>   The half-open range of integers; defaults to one period from zero, or to 0..24 for repl
+  -f, --format <FORMAT>      The output format: plain, csv, or json; defaults to plain
+      --rows <ROWS>          The rows drawn by render or svg: sieve, residuals, or subtrees; defaults to residuals
+      --ticks <N>            Draw an axis below the rows of render or svg with a tick every N integers
+      --ascii                Draw the rows of render with `#` and `.` rather than `█` and `·`
+      --annotate             Label each node of dot with the period and density of its subtree
+  -h, --help                 Print this message
+  -V, --version              Print the version
+
+The exit status is 0 on success, 1 if `contains` finds an integer not contained in the Sieve, and 2 on error.";
+
+/// The output format of a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Plain,
+    Csv,
+    Json,
+}
+
+/// The commands, options, and arguments given on the command line.
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    command: String,
+    expression: String,
+    range: Option<Range<i128>>,
+    format: Format,
+    render: RenderOptions,
+    annotate: bool,
+    values: Vec<String>,
+}
+
+/// A request to print a message and exit rather than to run a command.
+#[derive(Debug, PartialEq, Eq)]
+enum Parsed {
+    Run(Options),
+    Help,
+    Version,
+}
+
+/// A value reported by a command, formatted according to its type in JSON.
+enum Value {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(v) => write!(f, "{v}"),
+            Value::Float(v) => write!(f, "{v:.6}"),
+            Value::Bool(v) => write!(f, "{v}"),
+            Value::Str(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+impl Value {
+    /// Return the JSON representation of this value.
+    fn to_json(&self) -> String {
+        match self {
+            Value::Str(v) => json_string(v),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// Return a JSON string literal for `value`, escaping quotes, backslashes, and control characters.
+fn json_string(value: &str) -> String {
+    let mut post = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => post.push_str("\\\""),
+            '\\' => post.push_str("\\\\"),
+            '\n' => post.push_str("\\n"),
+            c if (c as u32) < 0x20 => post.push_str(&format!("\\u{:04x}", c as u32)),
+            c => post.push(c),
+        }
+    }
+    post.push('"');
+    post
+}
+
+/// Parse a half-open range notated `START..END`.
+fn parse_range(value: &str) -> Result<Range<i128>, String> {
+    let invalid = || format!("Cannot parse range: {value} (expected START..END).");
+    let (start, end) = value.split_once("..").ok_or_else(invalid)?;
+    let start = start.trim().parse::<i128>().map_err(|_e| invalid())?;
+    let end = end.trim().parse::<i128>().map_err(|_e| invalid())?;
+    if start > end {
+        return Err(invalid());
+    }
+    Ok(start..end)
+}
+
+/// Parse an output format name.
+fn parse_format(value: &str) -> Result<Format, String> {
+    match value {
+        "plain" => Ok(Format::Plain),
+        "csv" => Ok(Format::Csv),
+        "json" => Ok(Format::Json),
+        _ => Err(format!(
+            "Unknown format: {value} (expected plain, csv, or json)."
+        )),
+    }
+}
+
+/// Parse the rows drawn by `render`.
+fn parse_rows(value: &str) -> Result<RenderRows, String> {
+    match value {
+        "sieve" => Ok(RenderRows::Sieve),
+        "residuals" => Ok(RenderRows::Residuals),
+        "subtrees" => Ok(RenderRows::Subtrees),
+        _ => Err(format!(
+            "Unknown rows: {value} (expected sieve, residuals, or subtrees)."
+        )),
+    }
+}
+
+/// Return `true` if the argument is an option rather than a positional argument; negative integers are positional.
+fn is_option(arg: &str) -> bool {
+    arg.starts_with('-') && arg.len() > 1 && !arg[1..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Parse the command-line arguments, excluding the program name. Options may be given before or after positional arguments; all arguments after `--` are positional.
+fn parse_args(args: &[String]) -> Result<Parsed, String> {
+    let mut positional: Vec<String> = Vec::new();
+    let mut range: Option<Range<i128>> = None;
+    let mut format = Format::Plain;
+    let mut render = RenderOptions::default();
+    let mut annotate = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if !is_option(arg) {
+            positional.push(arg.clone());
+            continue;
+        }
+        // support both `--range 0..10` and `--range=0..10`
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| format!("Missing value for option: {name}."))
+        };
+        match name {
+            "-h" | "--help" => return Ok(Parsed::Help),
+            "-V" | "--version" => return Ok(Parsed::Version),
+            "-r" | "--range" => range = Some(parse_range(&value()?)?),
+            "-f" | "--format" => format = parse_format(&value()?)?,
+            "--rows" => render.rows = parse_rows(&value()?)?,
+            "--ticks" => {
+                let v = value()?;
+                match v.parse::<u64>() {
+                    Ok(ticks) if ticks > 0 => render.ticks = Some(ticks),
+                    _ => {
+                        return Err(format!(
+                            "Cannot parse ticks: {v} (expected a positive integer)."
+                        ))
+                    }
+                }
+            }
+            "--annotate" => annotate = true,
+            "--ascii" => {
+                let ascii = RenderOptions::ascii();
+                render.filled = ascii.filled;
+                render.empty = ascii.empty;
+            }
+            "--" => {
+                positional.extend(args.by_ref().cloned());
+            }
+            _ => return Err(format!("Unknown option: {arg}.")),
+        }
+    }
+    let mut positional = positional.into_iter();
+    let command = positional.next().ok_or("Missing command.")?;
+    // an interactive session does not take an expression
+    let expression = match positional.next() {
+        Some(expression) if command == "repl" => {
+            return Err(format!("Unexpected argument: {expression}."))
+        }
+        Some(expression) => expression,
+        None if command == "repl" => String::new(),
+        None => return Err(String::from("Missing Sieve expression.")),
+    };
+    let values: Vec<String> = positional.collect();
+    if !values.is_empty() && command != "contains" {
+        return Err(format!("Unexpected argument: {}.", values[0]));
+    }
+    Ok(Parsed::Run(Options {
+        command,
+        expression,
+        range,
+        format,
+        render,
+        annotate,
+        values,
+    }))
+}
+
+/// Write a sequence of values: one per line in plain text, a single row in CSV, or an array in JSON.
+fn write_sequence<W, I>(out: &mut W, format: Format, values: I) -> io::Result<()>
+where
+    W: Write,
+    I: Iterator<Item = Value>,
+{
+    let values: Vec<String> = match format {
+        Format::Json => values.map(|v| v.to_json()).collect(),
+        _ => values.map(|v| v.to_string()).collect(),
+    };
+    match format {
+        Format::Plain => {
+            for v in values {
+                writeln!(out, "{v}")?;
+            }
+            Ok(())
+        }
+        Format::Csv => writeln!(out, "{}", values.join(",")),
+        Format::Json => writeln!(out, "[{}]", values.join(",")),
+    }
+}
+
+/// Write named values: `name: value` lines in plain text, `name,value` rows in CSV, or an object in JSON.
+fn write_record<W: Write>(out: &mut W, format: Format, record: &[(&str, Value)]) -> io::Result<()> {
+    match format {
+        Format::Plain => {
+            for (name, v) in record {
+                writeln!(out, "{name}: {v}")?;
+            }
+            Ok(())
+        }
+        Format::Csv => {
+            writeln!(out, "name,value")?;
+            for (name, v) in record {
+                writeln!(out, "{name},{v}")?;
+            }
+            Ok(())
+        }
+        Format::Json => {
+            let fields: Vec<String> = record
+                .iter()
+                .map(|(name, v)| format!("{}:{}", json_string(name), v.to_json()))
+                .collect();
+            writeln!(out, "{{{}}}", fields.join(","))
+        }
+    }
+}
+
+/// Write a single value; in JSON, strings are quoted.
+fn write_value<W: Write>(out: &mut W, format: Format, value: Value) -> io::Result<()> {
+    match format {
+        Format::Json => writeln!(out, "{}", value.to_json()),
+        _ => writeln!(out, "{value}"),
+    }
+}
+
+/// Read integers from `input`, separated by whitespace or commas.
+fn read_values<R: BufRead>(input: R) -> Result<Vec<String>, String> {
+    let mut post: Vec<String> = Vec::new();
+    for line in input.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        post.extend(
+            line.split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|v| !v.is_empty())
+                .map(String::from),
+        );
+    }
+    Ok(post)
+}
+
+/// Run a command, writing results to `out`. Returns `false` if `contains` finds an integer that is not contained in the Sieve.
+fn run<R, W>(options: &Options, input: R, out: &mut W) -> Result<bool, String>
+where
+    R: BufRead,
+    W: Write,
+{
+    let io_error = |e: io::Error| e.to_string();
+    if options.command == "repl" {
+        let window = options.range.clone().unwrap_or(0..24);
+        repl::run(input, out, window).map_err(io_error)?;
+        return Ok(true);
+    }
+    let expr = options.expression.as_str();
+    let render = |e: SieveError| e.render(expr);
+    let sieve = Sieve::try_new(expr).map_err(render)?;
+    let range = || -> Result<Range<i128>, String> {
+        match &options.range {
+            Some(range) => Ok(range.clone()),
+            None => Ok(0..i128::from(sieve.period().map_err(render)?)),
+        }
+    };
+    let format = options.format;
+
+    match options.command.as_str() {
+        "values" => {
+            let values = sieve.values_in(range()?).map_err(render)?;
+            write_sequence(out, format, values.map(Value::Int)).map_err(io_error)?;
+        }
+        "states" => {
+            let states = sieve.iter_state(range()?);
+            write_sequence(out, format, states.map(Value::Bool)).map_err(io_error)?;
+        }
+        "intervals" => {
+            let intervals = sieve.iter_interval(range()?);
+            write_sequence(out, format, intervals.map(Value::Int)).map_err(io_error)?;
+        }
+        "contains" => {
+            let values = if options.values.is_empty() {
+                read_values(input)?
+            } else {
+                options.values.clone()
+            };
+            let mut pairs: Vec<(i128, bool)> = Vec::with_capacity(values.len());
+            for v in &values {
+                let v = v
+                    .parse::<i128>()
+                    .map_err(|_e| format!("Cannot parse integer: {v}."))?;
+                pairs.push((v, sieve.contains(v)));
+            }
+            match format {
+                Format::Plain => {
+                    for (v, state) in &pairs {
+                        writeln!(out, "{v} {state}").map_err(io_error)?;
+                    }
+                }
+                Format::Csv => {
+                    writeln!(out, "value,contains").map_err(io_error)?;
+                    for (v, state) in &pairs {
+                        writeln!(out, "{v},{state}").map_err(io_error)?;
+                    }
+                }
+                Format::Json => {
+                    let items: Vec<String> = pairs
+                        .iter()
+                        .map(|(v, state)| format!("{{\"value\":{v},\"contains\":{state}}}"))
+                        .collect();
+                    writeln!(out, "[{}]", items.join(",")).map_err(io_error)?;
+                }
+            }
+            return Ok(pairs.iter().all(|(_, state)| *state));
+        }
+        "period" => {
+            let period = sieve.period().map_err(render)?;
+            write_value(out, format, Value::Int(i128::from(period))).map_err(io_error)?;
+        }
+        "normalize" => {
+            let normal = sieve.to_normal_form().map_err(render)?;
+            write_value(out, format, Value::Str(normal.to_string())).map_err(io_error)?;
+        }
+        "explain" => {
+            let period = sieve.period().map_err(render)?;
+            let members = sieve.count_in(0..i128::from(period)).map_err(render)?;
+            let record = [
+                ("expression", Value::Str(sieve.to_string())),
+                ("period", Value::Int(i128::from(period))),
+                ("members", Value::Int(members as i128)),
+                ("density", Value::Float(members as f64 / period as f64)),
+                ("empty", Value::Bool(members == 0)),
+                ("universal", Value::Bool(members == u128::from(period))),
+                (
+                    "normal_form",
+                    Value::Str(sieve.to_normal_form().map_err(render)?.to_string()),
+                ),
+            ];
+            write_record(out, format, &record).map_err(io_error)?;
+        }
+        "render" => {
+            let text = sieve.render_ascii_with(range()?, &options.render);
+            write_value(out, format, Value::Str(text)).map_err(io_error)?;
+        }
+        "svg" => {
+            let svg_options = SvgOptions {
+                rows: options.render.rows,
+                ticks: options.render.ticks,
+                ..SvgOptions::default()
+            };
+            let svg = sieve.to_svg(range()?, &svg_options);
+            match format {
+                Format::Json => writeln!(out, "{}", json_string(&svg)),
+                _ => write!(out, "{svg}"),
+            }
+            .map_err(io_error)?;
+        }
+        "dot" => {
+            let dot = if options.annotate {
+                sieve.to_dot_annotated().map_err(render)?
+            } else {
+                sieve.to_dot()
+            };
+            write_value(out, format, Value::Str(dot)).map_err(io_error)?;
+        }
+        command => return Err(format!("Unknown command: {command}.\n\n{USAGE}")),
+    }
+    Ok(true)
+}
+
 /// CLI entry point.
 #[rustfmt::skip]
 fn main() { // cov-excl-line
-    println!("xensieve"); // cov-excl-line
+    let args: Vec<String> = std::env::args().skip(1).collect(); // cov-excl-line
+    let stdout = io::stdout(); // cov-excl-line
+    let mut out = io::BufWriter::new(stdout.lock()); // cov-excl-line
+    let status = match parse_args(&args) { // cov-excl-line
+        Ok(Parsed::Help) => writeln!(out, "{USAGE}").map(|_| 0).unwrap_or(2), // cov-excl-line
+        Ok(Parsed::Version) => writeln!(out, "xensieve {}", env!("CARGO_PKG_VERSION")).map(|_| 0).unwrap_or(2), // cov-excl-line
+        Ok(Parsed::Run(options)) => match run(&options, io::stdin().lock(), &mut out) { // cov-excl-line
+            Ok(true) => 0, // cov-excl-line
+            Ok(false) => 1, // cov-excl-line
+            Err(e) => { // cov-excl-line
+                eprintln!("{e}"); // cov-excl-line
+                2 // cov-excl-line
+            } // cov-excl-line
+        }, // cov-excl-line
+        Err(e) => { // cov-excl-line
+            eprintln!("{e}\n\n{USAGE}"); // cov-excl-line
+            2 // cov-excl-line
+        } // cov-excl-line
+    }; // cov-excl-line
+    if out.flush().is_err() { // cov-excl-line
+        process::exit(2); // cov-excl-line
+    } // cov-excl-line
+    process::exit(status); // cov-excl-line
 } // cov-excl-line
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn run_str(values: &[&str], input: &str) -> Result<(bool, String), String> {
+        let options = match parse_args(&args(values))? {
+            Parsed::Run(options) => options,
+            _ => panic!("expected a command"),
+        };
+        let mut out: Vec<u8> = Vec::new();
+        let status = run(&options, input.as_bytes(), &mut out)?;
+        Ok((status, String::from_utf8(out).unwrap()))
+    }
+
+    #[test]
+    fn test_parse_range_a() {
+        assert_eq!(parse_range("0..10").unwrap(), 0..10);
+        assert_eq!(parse_range("-20..-5").unwrap(), -20..-5);
+        assert!(parse_range("10..0").is_err());
+        assert!(parse_range("0:10").is_err());
+        assert!(parse_range("a..3").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_a() {
+        assert_eq!(
+            parse_args(&args(&["values", "3@0", "--range=-5..5", "-f", "csv"])).unwrap(),
+            Parsed::Run(Options {
+                command: String::from("values"),
+                expression: String::from("3@0"),
+                range: Some(-5..5),
+                format: Format::Csv,
+                render: RenderOptions::default(),
+                annotate: false,
+                values: vec![],
+            })
+        );
+        assert_eq!(parse_args(&args(&["-h"])).unwrap(), Parsed::Help);
+        assert_eq!(
+            parse_args(&args(&["period", "-V"])).unwrap(),
+            Parsed::Version
+        );
+    }
+
+    #[test]
+    fn test_parse_args_b() {
+        // negative integers are positional
+        match parse_args(&args(&["contains", "3@0", "-3", "--", "-x"])).unwrap() {
+            Parsed::Run(options) => assert_eq!(options.values, vec!["-3", "-x"]),
+            _ => panic!("expected a command"),
+        }
+        assert!(parse_args(&args(&["values"])).is_err());
+        assert!(parse_args(&args(&["values", "3@0", "4"])).is_err());
+        assert!(parse_args(&args(&["values", "3@0", "--range"])).is_err());
+        assert!(parse_args(&args(&["values", "3@0", "--format", "xml"])).is_err());
+        assert!(parse_args(&args(&["values", "3@0", "--foo"])).is_err());
+        assert!(parse_args(&args(&["repl", "3@0"])).is_err());
+        assert!(parse_args(&args(&["render", "3@0", "--ticks", "0"])).is_err());
+        assert!(parse_args(&args(&["render", "3@0", "--rows", "all"])).is_err());
+        assert!(parse_args(&args(&["repl", "-r", "0..12"])).is_ok());
+    }
+
+    #[test]
+    fn test_run_a() {
+        let (_, out) = run_str(&["values", "3@0|4@1", "-r", "0..10"], "").unwrap();
+        assert_eq!(out, "0\n1\n3\n5\n6\n9\n");
+        let (_, out) = run_str(&["states", "3@0", "-r", "0..4", "-f", "json"], "").unwrap();
+        assert_eq!(out, "[true,false,false,true]\n");
+        let (_, out) = run_str(&["intervals", "3@0|4@1", "-f", "csv"], "").unwrap();
+        assert_eq!(out, "1,2,2,1,3\n");
+    }
+
+    #[test]
+    fn test_run_b() {
+        let (status, out) = run_str(&["contains", "3@0", "9", "-3"], "").unwrap();
+        assert!(status);
+        assert_eq!(out, "9 true\n-3 true\n");
+        let (status, out) = run_str(&["contains", "3@0", "-f", "csv"], "1, 3\n6\n").unwrap();
+        assert!(!status);
+        assert_eq!(out, "value,contains\n1,false\n3,true\n6,true\n");
+        assert!(run_str(&["contains", "3@0", "x"], "").is_err());
+    }
+
+    #[test]
+    fn test_run_c() {
+        let (_, out) = run_str(&["explain", "3@0|5@1", "-f", "json"], "").unwrap();
+        assert_eq!(
+            out,
+            "{\"expression\":\"Sieve{3@0|5@1}\",\"period\":15,\"members\":7,\"density\":0.466667,\"empty\":false,\"universal\":false,\"normal_form\":\"Sieve{3@0|15@1|15@11}\"}\n"
+        );
+        assert_eq!(
+            run_str(&["values", "3@0 + 4@1"], "").unwrap_err(),
+            "Found unsupported character: + (at 4).\n3@0 + 4@1\n    ^"
+        );
+        assert!(run_str(&["foo", "3@0"], "").is_err());
+    }
+
+    #[test]
+    fn test_run_d() {
+        let (_, out) = run_str(
+            &["render", "3@0|4@1", "-r", "0..12", "--ascii", "--ticks=4"],
+            "",
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "3@0      #..#..#..#..\n4@1      .#...#...#..\n3@0|4@1  ##.#.##..#..\n         +---+---+---\n         0   4   8\n"
+        );
+        let (_, out) = run_str(&["render", "!2@0", "--rows", "sieve", "-f", "json"], "").unwrap();
+        assert_eq!(out, "\"!(2@0)  ·█\"\n");
+    }
+
+    #[test]
+    fn test_json_string_a() {
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+    }
+}
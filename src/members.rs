@@ -0,0 +1,453 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+use crate::compiled::index_of;
+use crate::compiled::value_at;
+use crate::disjoint;
+use crate::CompiledSieve;
+use crate::Residual;
+use crate::SieveError;
+use crate::SieveNode;
+
+/// Return the smallest value of the Residual greater than or equal to `value`, or `None` if the value exceeds `i128`. The Residual must not be the null Residual.
+fn first_from(r: &Residual, value: i128) -> Option<i128> {
+    let m = r.modulus() as i128;
+    value.checked_add((r.shift() as i128 - value.rem_euclid(m)).rem_euclid(m))
+}
+
+/// Return the largest value of the Residual less than `value`, or `None` if the value exceeds `i128`. The Residual must not be the null Residual.
+fn last_before(r: &Residual, value: i128) -> Option<i128> {
+    let m = r.modulus() as i128;
+    let v = value.checked_sub(1)?;
+    v.checked_sub((v.rem_euclid(m) - r.shift() as i128).rem_euclid(m))
+}
+
+/// Return the number of values of the Residual in `0..value` for positive values, or the negative number of values in `value..0` for negative values. The Residual must not be the null Residual.
+fn rank_of(r: &Residual, value: i128) -> i128 {
+    let m = r.modulus() as i128;
+    value.div_euclid(m) + (value.rem_euclid(m) > r.shift() as i128) as i128
+}
+
+/// The members of a Sieve, either the sorted members of one period of a lookup table, or disjoint Residuals from which members are found arithmetically.
+#[derive(Clone, Debug)]
+pub(crate) enum Members {
+    Table { period: u64, offsets: Vec<u64> },
+    Residuals(Vec<Residual>),
+}
+
+impl Members {
+    /// Return the members of the node from a lookup table if the period is permitted by `CompiledSieve::DEFAULT_BUDGET`, or otherwise from the disjoint Residuals of the node. If neither is permitted, the error of compiling the lookup table is returned.
+    pub(crate) fn from_node(node: &SieveNode) -> Result<Self, SieveError> {
+        let budget = CompiledSieve::DEFAULT_BUDGET;
+        match CompiledSieve::from_node(node, budget) {
+            Ok(c) => {
+                let (period, offsets) = c.into_offsets();
+                Ok(Members::Table { period, offsets })
+            }
+            Err(e) => disjoint::to_disjoint(node, budget)
+                .map(Members::Residuals)
+                .map_err(|_| e),
+        }
+    }
+
+    /// Return `true` if the value is a member.
+    fn contains(&self, value: i128) -> bool {
+        match self {
+            Members::Table { period, offsets } => offsets
+                .binary_search(&(value.rem_euclid(*period as i128) as u64))
+                .is_ok(),
+            Members::Residuals(residuals) => residuals.iter().any(|r| r.contains(value)),
+        }
+    }
+
+    /// Return the smallest member greater than or equal to `value`.
+    pub(crate) fn first_from(&self, value: i128) -> Option<i128> {
+        match self {
+            Members::Table { period, offsets } => {
+                value_at(*period, offsets, index_of(*period, offsets, value))
+            }
+            Members::Residuals(residuals) => {
+                residuals.iter().filter_map(|r| first_from(r, value)).min()
+            }
+        }
+    }
+
+    /// Return the largest member less than `value`.
+    pub(crate) fn prev_before(&self, value: i128) -> Option<i128> {
+        match self {
+            Members::Table { period, offsets } => value_at(
+                *period,
+                offsets,
+                index_of(*period, offsets, value).checked_sub(1)?,
+            ),
+            Members::Residuals(residuals) => {
+                residuals.iter().filter_map(|r| last_before(r, value)).max()
+            }
+        }
+    }
+
+    /// Return the rank of the value, as defined by `Sieve::rank`. As the Residuals are disjoint, the rank is the sum of the ranks of each Residual.
+    pub(crate) fn rank(&self, value: i128) -> i128 {
+        match self {
+            Members::Table { period, offsets } => index_of(*period, offsets, value),
+            Members::Residuals(residuals) => residuals
+                .iter()
+                .fold(0, |sum: i128, r| sum.saturating_add(rank_of(r, value))),
+        }
+    }
+
+    /// Return the number of members within the range. For Residuals, the count of each Residual is the difference of its ranks, avoiding the overflow of a difference of summed ranks.
+    pub(crate) fn count_in(&self, range: Range<i128>) -> u128 {
+        if range.end <= range.start {
+            return 0;
+        }
+        match self {
+            Members::Table { period, offsets } => index_of(*period, offsets, range.end)
+                .abs_diff(index_of(*period, offsets, range.start)),
+            Members::Residuals(residuals) => residuals.iter().fold(0, |sum: u128, r| {
+                sum.saturating_add(rank_of(r, range.end).abs_diff(rank_of(r, range.start)))
+            }),
+        }
+    }
+
+    /// Return the member at the rank `k`. For Residuals, this is found by a binary search of the values for the smallest value whose rank, including the value, exceeds `k`.
+    pub(crate) fn select(&self, k: i128) -> Option<i128> {
+        match self {
+            Members::Table { period, offsets } => value_at(*period, offsets, k),
+            Members::Residuals(_) => {
+                let above = |v: i128| {
+                    let rank = self.rank(v);
+                    rank > k || (rank == k && self.contains(v))
+                };
+                if !above(i128::MAX) {
+                    return None;
+                }
+                let (mut lo, mut hi) = (i128::MIN, i128::MAX);
+                while lo < hi {
+                    // the mean, rounded down, without overflow
+                    let mid = (lo & hi) + ((lo ^ hi) >> 1);
+                    if above(mid) {
+                        hi = mid;
+                    } else {
+                        lo = mid + 1;
+                    }
+                }
+                // if the rank of the smallest value exceeds `k`, the member is less than `i128::MIN`
+                Some(lo).filter(|&v| self.rank(v) <= k)
+            }
+        }
+    }
+
+    /// Return the member `n` members from `start`, as defined by `Sieve::nth_from`.
+    pub(crate) fn nth_from(&self, start: i128, n: i128) -> Option<i128> {
+        match n {
+            0 => self.first_from(start),
+            -1 => self.prev_before(start),
+            _ => self.select(self.rank(start).checked_add(n)?),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The position of an `IterMembers`: for a lookup table, the next value and the index of its offset; for disjoint Residuals, the next value of each Residual, ordered such that the smallest is first.
+#[derive(Clone, Debug)]
+enum Position {
+    Table {
+        period: i128,
+        offsets: Vec<u64>,
+        value: Option<i128>,
+        index: usize,
+    },
+    Residuals {
+        residuals: Vec<Residual>,
+        heap: BinaryHeap<Reverse<(i128, usize)>>,
+    },
+}
+
+/// The iterator returned by `iter_from` and `values_in`.
+/// ```
+/// let s = xensieve::Sieve::new("3@0|4@0");
+/// let mut s_iter = s.iter_from(17).unwrap();
+/// assert_eq!(s_iter.next().unwrap(), 18);
+/// assert_eq!(s_iter.next().unwrap(), 20);
+/// ```
+#[derive(Clone, Debug)]
+pub struct IterMembers {
+    position: Position,
+    end: Option<i128>,
+}
+
+impl IterMembers {
+    pub(crate) fn new(members: Members, start: i128, end: Option<i128>) -> Self {
+        let position = match members {
+            Members::Table { period, offsets } => {
+                let period = period as i128;
+                // relative to `start` to avoid overflow at the limits of `i128`
+                let pos = start.rem_euclid(period);
+                let (value, index) = match offsets.partition_point(|&o| (o as i128) < pos) {
+                    _ if offsets.is_empty() => (None, 0),
+                    i if i == offsets.len() => {
+                        (start.checked_add(period - pos + offsets[0] as i128), 0)
+                    }
+                    i => (start.checked_add(offsets[i] as i128 - pos), i),
+                };
+                Position::Table {
+                    period,
+                    offsets,
+                    value,
+                    index,
+                }
+            }
+            Members::Residuals(residuals) => {
+                let heap = residuals
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, r)| Some(Reverse((first_from(r, start)?, i))))
+                    .collect();
+                Position::Residuals { residuals, heap }
+            }
+        };
+        Self { position, end }
+    }
+}
+
+impl Iterator for IterMembers {
+    type Item = i128;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = match &mut self.position {
+            Position::Table {
+                period,
+                offsets,
+                value,
+                index,
+            } => {
+                let v = (*value)?;
+                if self.end.map_or(false, |e| v >= e) {
+                    *value = None;
+                    return None;
+                }
+                let current = offsets[*index] as i128;
+                *index += 1;
+                let step = if *index == offsets.len() {
+                    *index = 0;
+                    *period - current + offsets[0] as i128
+                } else {
+                    offsets[*index] as i128 - current
+                };
+                *value = v.checked_add(step);
+                v
+            }
+            Position::Residuals { residuals, heap } => {
+                let Reverse((v, i)) = *heap.peek()?;
+                if self.end.map_or(false, |e| v >= e) {
+                    heap.clear();
+                    return None;
+                }
+                heap.pop();
+                // as the Residuals are disjoint, no value is produced twice
+                if let Some(n) = v.checked_add(residuals[i].modulus() as i128) {
+                    heap.push(Reverse((n, i)));
+                }
+                v
+            }
+        };
+        Some(value)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// A bidirectional cursor positioned between the members of a Sieve. Moving forward with `next` yields values greater than or equal to the position in ascending order; moving backward with `prev` yields values less than the position in descending order. Each move places the cursor on the other side of the value yielded, such that `prev` after `next` yields the same value. Skipping with `nth` and `nth_prev` jumps directly to the destination member.
+///
+/// ```
+/// let s = xensieve::Sieve::new("3@0|4@0");
+/// let mut c = s.cursor(5).unwrap();
+/// assert_eq!(c.next(), Some(6));
+/// assert_eq!(c.next(), Some(8));
+/// assert_eq!(c.prev(), Some(8));
+/// assert_eq!(c.prev(), Some(6));
+/// assert_eq!(c.nth_prev(2), Some(0));
+/// assert_eq!(c.nth(500), Some(1000));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SieveCursor {
+    members: Members,
+    // the smallest value that may be yielded by `next`, or `None` if beyond `i128::MAX`
+    position: Option<i128>,
+}
+
+impl SieveCursor {
+    pub(crate) fn new(members: Members, start: i128) -> Self {
+        Self {
+            members,
+            position: Some(start),
+        }
+    }
+
+    /// Return the value `n` members before the position, where zero is the largest member less than the position.
+    fn before(&self, n: usize) -> Option<i128> {
+        let n = n as i128;
+        match self.position {
+            Some(p) => self.members.nth_from(p, -1 - n),
+            // beyond `i128::MAX`, which is the first member before the position if contained
+            None if self.members.contains(i128::MAX) => match n {
+                0 => Some(i128::MAX),
+                _ => self.members.nth_from(i128::MAX, -n),
+            },
+            None => self.members.nth_from(i128::MAX, -1 - n),
+        }
+    }
+
+    /// Return the value that would be returned by `next` without moving the cursor.
+    pub fn peek(&self) -> Option<i128> {
+        self.members.first_from(self.position?)
+    }
+
+    /// Return the value that would be returned by `prev` without moving the cursor.
+    pub fn peek_prev(&self) -> Option<i128> {
+        self.before(0)
+    }
+
+    /// Move the cursor backward, returning the largest member less than the position, or `None`, without moving, if there is no such member.
+    pub fn prev(&mut self) -> Option<i128> {
+        self.nth_prev(0)
+    }
+
+    /// Move the cursor backward past `n` members, returning the next member less than those, or `None`, without moving, if there is no such member.
+    pub fn nth_prev(&mut self, n: usize) -> Option<i128> {
+        let value = self.before(n)?;
+        self.position = Some(value);
+        Some(value)
+    }
+}
+
+impl Iterator for SieveCursor {
+    type Item = i128;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let value = self.members.nth_from(self.position?, n as i128)?;
+        self.position = value.checked_add(1);
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_first_from_a() {
+        let r = Residual::new(12, 4);
+        assert_eq!(first_from(&r, 4), Some(4));
+        assert_eq!(first_from(&r, 5), Some(16));
+        assert_eq!(first_from(&r, -9), Some(-8));
+        assert_eq!(first_from(&r, i128::MAX), None);
+    }
+
+    #[test]
+    fn test_last_before_a() {
+        let r = Residual::new(12, 4);
+        assert_eq!(last_before(&r, 4), Some(-8));
+        assert_eq!(last_before(&r, 5), Some(4));
+        assert_eq!(last_before(&r, i128::MIN), None);
+        assert_eq!(rank_of(&r, 4), 0);
+        assert_eq!(rank_of(&r, 5), 1);
+        assert_eq!(rank_of(&r, -8), -1);
+    }
+
+    #[test]
+    fn test_members_b() {
+        // the same members are navigated from a lookup table and from Residuals
+        let s1 = Sieve::new("(5@0|4@2)&!30@10");
+        let m1 = Members::from_node(&s1.root).unwrap();
+        let m2 = Members::Residuals(disjoint::to_disjoint(&s1.root, 1 << 16).unwrap());
+        for v in -70..70 {
+            assert_eq!(m1.first_from(v), m2.first_from(v));
+            assert_eq!(m1.prev_before(v), m2.prev_before(v));
+            assert_eq!(m1.rank(v), m2.rank(v));
+            assert_eq!(m1.select(v), m2.select(v));
+            assert_eq!(m1.count_in(v..v + 45), m2.count_in(v..v + 45));
+            for n in -4..4 {
+                assert_eq!(m1.nth_from(v, n), m2.nth_from(v, n));
+            }
+        }
+    }
+
+    #[test]
+    fn test_members_c() {
+        let m1 = Members::Residuals(vec![Residual::new(1, 0)]);
+        assert_eq!(m1.select(i128::MAX), Some(i128::MAX));
+        assert_eq!(m1.select(i128::MIN), Some(i128::MIN));
+        let m2 = Members::Residuals(vec![Residual::new(3, 0)]);
+        assert_eq!(m2.select(i128::MAX), None);
+        assert_eq!(m2.select(i128::MIN), None);
+        assert_eq!(m2.nth_from(0, i128::MAX), None);
+        assert_eq!(m1.count_in(i128::MIN..i128::MAX), u128::MAX);
+        assert_eq!(m2.count_in(i128::MIN..i128::MAX), u128::MAX / 3);
+        let m3 = Members::Residuals(vec![]);
+        assert_eq!(m3.select(0), None);
+        assert_eq!(m3.select(-1), None);
+        assert_eq!(m3.prev_before(0), None);
+    }
+
+    #[test]
+    fn test_members_a() {
+        let s1 = Sieve::new("3@0|4@0");
+        assert!(matches!(
+            Members::from_node(&s1.root).unwrap(),
+            Members::Table { period: 12, .. }
+        ));
+        let s2 = Sieve::new("1000000007@3|1000000007@5");
+        assert!(matches!(
+            Members::from_node(&s2.root).unwrap(),
+            Members::Residuals(_)
+        ));
+        let s3 = Sieve::new("1000000007@0|1000000009@0");
+        assert!(Members::from_node(&s3.root).is_err());
+    }
+
+    #[test]
+    fn test_iter_members_a() {
+        // the same values are found from a lookup table and from Residuals
+        let s1 = Sieve::new("(5@0|4@2)&!30@10");
+        let residuals = disjoint::to_disjoint(&s1.root, 1 << 16).unwrap();
+        let post1: Vec<_> = IterMembers::new(Members::from_node(&s1.root).unwrap(), -130, None)
+            .take(100)
+            .collect();
+        let post2: Vec<_> = IterMembers::new(Members::Residuals(residuals), -130, None)
+            .take(100)
+            .collect();
+        assert_eq!(post1, post2);
+        assert_eq!(post1, s1.iter_value(-130..).take(100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_iter_members_b() {
+        let members = Members::Residuals(vec![Residual::new(7, 3)]);
+        let mut it = IterMembers::new(members.clone(), i128::MAX - 20, None);
+        assert_eq!(it.by_ref().count(), 3);
+        assert_eq!(it.next(), None);
+        let it = IterMembers::new(members, 0, Some(24));
+        assert_eq!(it.collect::<Vec<_>>(), vec![3, 10, 17]);
+        let it = IterMembers::new(Members::Residuals(vec![]), 0, None);
+        assert_eq!(it.count(), 0);
+    }
+
+    #[test]
+    fn test_iter_members_c() {
+        // the next member of the lookup table exceeds `i128`
+        let s1 = Sieve::new("7@3");
+        assert_eq!(s1.iter_from(i128::MAX - 1).unwrap().count(), 0);
+        assert_eq!(s1.iter_from(i128::MAX - 5).unwrap().count(), 1);
+        let it = IterMembers::new(Members::from_node(&s1.root).unwrap(), i128::MAX - 1, None);
+        assert_eq!(it.count(), 0);
+    }
+}
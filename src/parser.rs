@@ -1,89 +1,502 @@
 use std::collections::VecDeque;
 
+use crate::SieveError;
+use crate::Span;
+
+/// A token in a Sieve expression, either an operand or an operator, and its location in the expression.
+#[derive(Clone, Debug)]
+pub(crate) struct Token {
+    pub(crate) value: String,
+    pub(crate) span: Span,
+}
+
 /// Given a Residual string representation, parse it into two integers.
-pub(crate) fn residual_to_ints(value: &str) -> Result<(u64, u64), &'static str> {
+pub(crate) fn residual_to_ints(value: &str, span: Span) -> Result<(u64, u64), SieveError> {
+    let malformed = || SieveError::MalformedResidual {
+        residual: value.to_string(),
+        span,
+    };
     let parts: Vec<&str> = value.split('@').collect();
     if parts.len() != 2 {
-        return Err("Input must contain one '@' character separating two numbers.");
+        return Err(malformed());
     }
-    let m = parts[0]
-        .parse::<u64>()
-        .map_err(|_e| "Residual error parsing modulus")?;
-    let s = parts[1]
-        .parse::<u64>()
-        .map_err(|_e| "Residual error parsing shift")?;
-    Ok((m, s))
+    let m = parts[0].parse::<u64>().map_err(|_e| malformed())?;
+    let s = parts[1].parse::<i128>().map_err(|_e| malformed())?;
+    if let Ok(s) = u64::try_from(s) {
+        return Ok((m, s));
+    }
+    // a negative or large shift is normalized to be less than the modulus
+    if m == 0 {
+        return Ok((0, 0));
+    }
+    Ok((m, s.rem_euclid(m as i128) as u64))
 }
 
-/// Operator precedence for `infix_to_postfix`.
+/// Operator precedence for `infix_to_postfix`, also used to place parentheses when displaying a Sieve.
 #[inline(always)]
-fn char_to_precedence(op: char) -> i8 {
+pub(crate) const fn char_to_precedence(op: char) -> i8 {
     match op {
         '!' => 4,
-        '&' => 3,
+        '&' | '-' => 3,
         '^' => 2,
         '|' => 1,
         _ => 0,
     }
 }
 
-/// Utility function to reduce repetition in `infix_to_postfix`.
+/// Utility function to reduce repetition in `infix_to_postfix`. The `start` and `stop` are the byte offsets of the first character of the operand and one past its last character; as whitespace within an operand is ignored, the span may be longer than the operand.
 #[inline(always)]
-fn collect_operand(post: &mut VecDeque<String>, operand: &mut String) {
+fn collect_operand(post: &mut VecDeque<Token>, operand: &mut String, start: usize, stop: usize) {
     if !operand.is_empty() {
-        post.push_back(operand.clone());
+        post.push_back(Token {
+            value: operand.clone(),
+            span: Span::new(start, stop - start),
+        });
         operand.clear();
     }
 }
 
+/// Utility function to convert an operator, and its location, into a `Token`.
+#[inline(always)]
+fn operator_to_token(op: char, offset: usize) -> Token {
+    Token {
+        value: op.to_string(),
+        span: Span::new(offset, op.len_utf8()),
+    }
+}
+
+/// If the expression is wrapped in the `Sieve{...}` notation used by `Display`, return the byte range of the contained expression; otherwise, return the byte range of the entire expression.
+pub(crate) fn unwrap_display(expr: &str) -> (usize, usize) {
+    let trimmed = expr.trim();
+    if trimmed.starts_with("Sieve{") && trimmed.ends_with('}') {
+        let start = expr.len() - expr.trim_start().len() + "Sieve{".len();
+        let end = expr.trim_end().len() - 1;
+        return (start, end);
+    }
+    (0, expr.len())
+}
+
+/// Return `true` if the operand is a name, starting with a letter or underscore and followed by letters, digits, or underscores.
+pub(crate) fn is_name(value: &str) -> bool {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+/// A statement of a Sieve string expression, either a binding of a name to an expression, or an expression alone.
+///
+/// # Fields
+/// * `name` - The name of a binding, if any.
+/// * `start` - The byte offset of the start of the expression.
+/// * `end` - The byte offset of the end of the expression.
+///
+pub(crate) struct Statement {
+    pub(crate) name: Option<Token>,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+/// Split an expression into statements separated by `;`, where each statement is either `name = expression` or an expression alone. Blank statements are dropped, unless the expression has only one statement.
+pub(crate) fn split_statements(expr: &str) -> Result<Vec<Statement>, SieveError> {
+    let (base, end) = unwrap_display(expr);
+    let mut post: Vec<Statement> = Vec::new();
+    let mut start = base;
+    for part in expr[base..end].split(';') {
+        let stop = start + part.len();
+        if !part.trim().is_empty() || (start == base && stop == end) {
+            let statement = match part.find('=') {
+                Some(i) => {
+                    let lhs = &part[..i];
+                    let name = lhs.trim();
+                    let offset = start + lhs.len() - lhs.trim_start().len();
+                    let span = Span::new(offset, name.len());
+                    if !is_name(name) {
+                        return Err(SieveError::MalformedBinding {
+                            binding: name.to_string(),
+                            span,
+                        });
+                    }
+                    Statement {
+                        name: Some(Token {
+                            value: name.to_string(),
+                            span,
+                        }),
+                        start: start + i + 1,
+                        end: stop,
+                    }
+                }
+                None => Statement {
+                    name: None,
+                    start,
+                    end: stop,
+                },
+            };
+            post.push(statement);
+        }
+        // skip the separator
+        start = stop + 1;
+    }
+    Ok(post)
+}
+
 // Implementation of Shunting yard algorithm for Sieve expressions.
-pub(crate) fn infix_to_postfix(expr: &str) -> Result<VecDeque<String>, String> {
-    let mut post: VecDeque<String> = VecDeque::new();
-    let mut operators: Vec<char> = Vec::new();
+#[cfg(test)]
+pub(crate) fn infix_to_postfix(expr: &str) -> Result<VecDeque<Token>, SieveError> {
+    let (base, end) = unwrap_display(expr);
+    infix_to_postfix_range(expr, base, end)
+}
+
+/// Implementation of `infix_to_postfix` for the byte range `base..end` of the expression.
+pub(crate) fn infix_to_postfix_range(
+    expr: &str,
+    base: usize,
+    end: usize,
+) -> Result<VecDeque<Token>, SieveError> {
+    let mut post: VecDeque<Token> = VecDeque::new();
+    let mut operators: Vec<(char, usize)> = Vec::new();
     let mut operand: String = String::new();
+    let mut start: usize = 0;
+    let mut stop: usize = 0;
 
-    for c in expr.chars() {
+    for (i, c) in expr[base..end].char_indices() {
+        // report locations relative to the complete expression
+        let i = i + base;
         match c {
-            '0'..='9' | '@' => operand.push(c), // operand characters
-            '!' => operators.push(c),
-            '|' | '&' | '^' => {
+            '0'..='9' | '@' | '_' | 'a'..='z' | 'A'..='Z' => {
+                // operand characters, of Residuals or names
+                if operand.is_empty() {
+                    start = i;
+                }
+                operand.push(c);
+                stop = i + 1;
+            }
+            '!' => {
+                collect_operand(&mut post, &mut operand, start, stop);
+                operators.push((c, i));
+            }
+            '-' if operand.ends_with('@') => {
+                // the sign of a negative shift
+                operand.push(c);
+                stop = i + 1;
+            }
+            '|' | '&' | '^' | '-' => {
                 // all binary operators
-                collect_operand(&mut post, &mut operand);
-                while let Some(&top) = operators.last() {
+                collect_operand(&mut post, &mut operand, start, stop);
+                while let Some(&(top, j)) = operators.last() {
                     if top == '(' || char_to_precedence(top) < char_to_precedence(c) {
                         break;
                     }
-                    post.push_back(operators.pop().unwrap().to_string())
+                    operators.pop();
+                    post.push_back(operator_to_token(top, j))
                 }
-                operators.push(c);
+                operators.push((c, i));
+            }
+            '(' => {
+                collect_operand(&mut post, &mut operand, start, stop);
+                operators.push((c, i));
             }
-            '(' => operators.push(c),
             ')' => {
-                collect_operand(&mut post, &mut operand);
-                while let Some(top) = operators.pop() {
-                    if top == '(' {
-                        break;
+                collect_operand(&mut post, &mut operand, start, stop);
+                loop {
+                    match operators.pop() {
+                        Some(('(', _)) => break,
+                        Some((top, j)) => post.push_back(operator_to_token(top, j)),
+                        None => {
+                            return Err(SieveError::UnbalancedParenthesis {
+                                parenthesis: c,
+                                span: Span::new(i, 1),
+                            })
+                        }
                     }
-                    post.push_back(top.to_string())
                 }
             }
+            // whitespace is ignored, including within an operand
             _ if c.is_whitespace() => {}
             _ => {
-                return Err(format!("Found unsupported operator: {}.", c));
+                return Err(SieveError::UnsupportedCharacter {
+                    character: c,
+                    span: Span::new(i, c.len_utf8()),
+                });
             }
         }
     }
     // get any remaining numbers
-    if !operand.is_empty() {
-        post.push_back(operand);
-    }
+    collect_operand(&mut post, &mut operand, start, stop);
     // get any remaining operators
-    while let Some(op) = operators.pop() {
-        post.push_back(op.to_string());
+    while let Some((op, j)) = operators.pop() {
+        if op == '(' {
+            return Err(SieveError::UnbalancedParenthesis {
+                parenthesis: op,
+                span: Span::new(j, 1),
+            });
+        }
+        post.push_back(operator_to_token(op, j));
     }
     Ok(post)
 }
 
+//------------------------------------------------------------------------------
+// Compile-time parsing, used by the `sieve!` macro. These functions implement the same grammar as `infix_to_postfix` with `const fn`, such that a malformed expression is reported as an error during compilation.
+
+/// A token of a Sieve expression in postfix order, either a Residual as a modulus and a shift, or an operator.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstToken {
+    Residual(u64, u64),
+    Operator(u8),
+}
+
+/// Return `true` if the byte is part of an operand, of a Residual or a name.
+const fn is_operand_byte(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'@' || c == b'_'
+}
+
+/// Return the position of the first byte at or after `i` that is not whitespace, or `end`.
+const fn skip_whitespace(bytes: &[u8], mut i: usize, end: usize) -> usize {
+    while i < end && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+/// Return `true` if the byte at `i` extends the operand in progress; a `-` after `@`, ignoring whitespace, is the sign of a negative shift.
+const fn extends_operand(bytes: &[u8], i: usize, in_operand: bool) -> bool {
+    if is_operand_byte(bytes[i]) {
+        return true;
+    }
+    if bytes[i] != b'-' || !in_operand {
+        return false;
+    }
+    let mut j = i;
+    while j > 0 && bytes[j - 1].is_ascii_whitespace() {
+        j -= 1;
+    }
+    j > 0 && bytes[j - 1] == b'@'
+}
+
+/// Implementation of `unwrap_display` as a `const fn`, returning the byte range of the expression within any `Sieve{...}` notation.
+const fn const_unwrap_display(bytes: &[u8]) -> (usize, usize) {
+    let prefix = b"Sieve{";
+    let mut start = 0;
+    while start < bytes.len() && bytes[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    let mut end = bytes.len();
+    while end > start && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    if end - start < prefix.len() + 1 || bytes[end - 1] != b'}' {
+        return (0, bytes.len());
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+        if bytes[start + i] != prefix[i] {
+            return (0, bytes.len());
+        }
+        i += 1;
+    }
+    (start + prefix.len(), end - 1)
+}
+
+/// Return the number of tokens in the postfix form of the expression, excluding parentheses.
+#[doc(hidden)]
+pub const fn const_postfix_len(expr: &str) -> usize {
+    let bytes = expr.as_bytes();
+    let (base, end) = const_unwrap_display(bytes);
+    let mut count = 0;
+    let mut in_operand = false;
+    let mut i = base;
+    while i < end {
+        if bytes[i].is_ascii_whitespace() {
+            // whitespace is ignored, including within an operand
+        } else if extends_operand(bytes, i, in_operand) {
+            if !in_operand {
+                count += 1;
+                in_operand = true;
+            }
+        } else {
+            in_operand = false;
+            if matches!(bytes[i], b'!' | b'&' | b'^' | b'|' | b'-') {
+                count += 1;
+            }
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Parse the operand in the byte range `start..end` as a Residual, ignoring whitespace. Names cannot be bound at compile time, and are rejected.
+const fn const_residual(bytes: &[u8], start: usize, end: usize) -> ConstToken {
+    let mut i = start;
+    while i < end && bytes[i] != b'@' {
+        i += 1;
+    }
+    if i == end && !bytes[start].is_ascii_digit() {
+        panic!("Undefined name: names cannot be used in sieve! expressions.");
+    }
+    i = start;
+    let mut m: u64 = 0;
+    while i < end && bytes[i].is_ascii_digit() {
+        m = match m.checked_mul(10) {
+            Some(v) => match v.checked_add((bytes[i] - b'0') as u64) {
+                Some(v) => v,
+                None => panic!("Cannot parse Residual: modulus exceeds u64."),
+            },
+            None => panic!("Cannot parse Residual: modulus exceeds u64."),
+        };
+        i = skip_whitespace(bytes, i + 1, end);
+    }
+    if i == start || i == end || bytes[i] != b'@' {
+        panic!("Cannot parse Residual: expected the form M@S.");
+    }
+    i = skip_whitespace(bytes, i + 1, end);
+    let negative = i < end && bytes[i] == b'-';
+    if negative {
+        i = skip_whitespace(bytes, i + 1, end);
+    }
+    let digits = i;
+    let mut s: i128 = 0;
+    while i < end && bytes[i].is_ascii_digit() {
+        s = match s.checked_mul(10) {
+            Some(v) => match v.checked_add((bytes[i] - b'0') as i128) {
+                Some(v) => v,
+                None => panic!("Cannot parse Residual: shift exceeds i128."),
+            },
+            None => panic!("Cannot parse Residual: shift exceeds i128."),
+        };
+        i = skip_whitespace(bytes, i + 1, end);
+    }
+    if i == digits || i != end {
+        panic!("Cannot parse Residual: expected the form M@S.");
+    }
+    if negative {
+        s = -s;
+    }
+    // as with `residual_to_ints`, the shift is normalized to be less than the modulus
+    if m == 0 {
+        return ConstToken::Residual(0, 0);
+    }
+    ConstToken::Residual(m, s.rem_euclid(m as i128) as u64)
+}
+
+/// Implementation of `infix_to_postfix` as a `const fn`, where `N` is the value returned by `const_postfix_len`. Rather than pushing parentheses on the operator stack, each operator retains the depth of parentheses at which it was pushed. Panics if the expression is malformed.
+#[doc(hidden)]
+pub const fn const_postfix<const N: usize>(expr: &str) -> [ConstToken; N] {
+    let bytes = expr.as_bytes();
+    let mut post = [ConstToken::Operator(0); N];
+    let mut count = 0;
+    let mut operators = [(0u8, 0usize); N];
+    let mut pending = 0;
+    let mut depth = 0;
+    let mut in_operand = false;
+    let (base, end) = const_unwrap_display(bytes);
+    let mut start = base;
+    let mut i = base;
+
+    // one position past the end of the expression collects any remaining operand
+    while i <= end {
+        if i < end && bytes[i].is_ascii_whitespace() {
+            // whitespace is ignored, including within an operand
+            i += 1;
+            continue;
+        }
+        if i < end && extends_operand(bytes, i, in_operand) {
+            if !in_operand {
+                start = i;
+                in_operand = true;
+            }
+            i += 1;
+            continue;
+        }
+        if in_operand {
+            post[count] = const_residual(bytes, start, i);
+            count += 1;
+            in_operand = false;
+        }
+        if i == end {
+            break;
+        }
+        let c = bytes[i];
+        match c {
+            b'!' => {
+                operators[pending] = (c, depth);
+                pending += 1;
+            }
+            b'|' | b'&' | b'^' | b'-' => {
+                while pending > 0 {
+                    let (top, d) = operators[pending - 1];
+                    if d != depth || char_to_precedence(top as char) < char_to_precedence(c as char)
+                    {
+                        break;
+                    }
+                    pending -= 1;
+                    post[count] = ConstToken::Operator(top);
+                    count += 1;
+                }
+                operators[pending] = (c, depth);
+                pending += 1;
+            }
+            b'(' => depth += 1,
+            b')' => {
+                if depth == 0 {
+                    panic!("Unbalanced parenthesis: )");
+                }
+                while pending > 0 && operators[pending - 1].1 == depth {
+                    pending -= 1;
+                    post[count] = ConstToken::Operator(operators[pending].0);
+                    count += 1;
+                }
+                depth -= 1;
+            }
+            _ if c.is_ascii_whitespace() => {}
+            _ => panic!("Found unsupported character."),
+        }
+        i += 1;
+    }
+    if depth > 0 {
+        panic!("Unbalanced parenthesis: (");
+    }
+    while pending > 0 {
+        pending -= 1;
+        post[count] = ConstToken::Operator(operators[pending].0);
+        count += 1;
+    }
+    if count != N {
+        panic!("Expression length does not match const_postfix_len.");
+    }
+    // evaluate the number of operands on the stack, as `SieveNode::try_from_range` would
+    let mut size = 0;
+    let mut j = 0;
+    while j < count {
+        match post[j] {
+            ConstToken::Residual(..) => size += 1,
+            ConstToken::Operator(b'!') => {
+                if size < 1 {
+                    panic!("Missing operand for operator: !");
+                }
+            }
+            ConstToken::Operator(_) => {
+                if size < 2 {
+                    panic!("Missing operand for binary operator.");
+                }
+                size -= 1;
+            }
+        }
+        j += 1;
+    }
+    if size == 0 {
+        panic!("Found an empty expression.");
+    }
+    if size > 1 {
+        panic!("Found operands without an operator.");
+    }
+    post
+}
+
 // to run cargo test and see stdout:
 // % cargo test test_infix_to_rpn_a -- --nocapture
 
@@ -93,38 +506,63 @@ mod tests {
 
     #[test]
     fn test_residual_to_ints_a() {
-        assert_eq!(residual_to_ints("3@1").unwrap(), (3, 1))
+        assert_eq!(residual_to_ints("3@1", Span::new(0, 3)).unwrap(), (3, 1))
     }
 
     #[test]
     fn test_residual_to_ints_b() {
-        assert_eq!(residual_to_ints("9@2").unwrap(), (9, 2))
+        assert_eq!(residual_to_ints("9@2", Span::new(0, 3)).unwrap(), (9, 2))
     }
 
     #[test]
     fn test_residual_to_ints_c() {
-        assert_eq!(residual_to_ints("0@5").unwrap(), (0, 5))
+        assert_eq!(residual_to_ints("0@5", Span::new(0, 3)).unwrap(), (0, 5))
     }
 
     #[test]
     fn test_residual_to_ints_d() {
-        assert!(residual_to_ints("0").is_err());
+        assert!(residual_to_ints("0", Span::new(0, 1)).is_err());
     }
 
     #[test]
     fn test_residual_to_ints_e() {
-        assert!(residual_to_ints("3@wer").is_err());
+        assert!(residual_to_ints("3@wer", Span::new(0, 5)).is_err());
     }
 
     #[test]
     fn test_residual_to_ints_f() {
-        assert!(residual_to_ints("foo@3").is_err());
+        assert!(residual_to_ints("foo@3", Span::new(0, 5)).is_err());
+    }
+
+    #[test]
+    fn test_residual_to_ints_h() {
+        assert_eq!(residual_to_ints("3@-1", Span::new(0, 4)).unwrap(), (3, 2));
+        assert_eq!(residual_to_ints("7@-14", Span::new(0, 5)).unwrap(), (7, 0));
+        assert_eq!(
+            residual_to_ints("5@123456789012345678901234567", Span::new(0, 29)).unwrap(),
+            (5, 2)
+        );
+        assert_eq!(residual_to_ints("0@-3", Span::new(0, 4)).unwrap(), (0, 0));
+        assert!(residual_to_ints("3@--1", Span::new(0, 5)).is_err());
+        assert!(residual_to_ints("-3@1", Span::new(0, 4)).is_err());
+    }
+
+    #[test]
+    fn test_residual_to_ints_g() {
+        assert_eq!(
+            residual_to_ints("3@@1", Span::new(2, 4)).unwrap_err(),
+            SieveError::MalformedResidual {
+                residual: String::from("3@@1"),
+                span: Span::new(2, 4)
+            }
+        );
     }
 
     #[test]
     fn test_char_to_precedence_a() {
         assert_eq!(char_to_precedence('!'), 4);
-        assert_eq!(char_to_precedence('-'), 0);
+        assert_eq!(char_to_precedence('-'), 3);
+        assert_eq!(char_to_precedence('+'), 0);
         assert_eq!(char_to_precedence('&'), 3);
     }
 
@@ -133,7 +571,7 @@ mod tests {
         let e1 = "!3@1 & 6@2 | !(10@0 | 2@0 | 3@0 )";
         let px1 = infix_to_postfix(e1).unwrap();
         assert_eq!(
-            px1.iter().collect::<Vec<_>>(),
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
             vec!["3@1", "!", "6@2", "&", "10@0", "2@0", "|", "3@0", "|", "!", "|"]
         );
     }
@@ -143,7 +581,7 @@ mod tests {
         let e1 = "10@0 | 2@0 | 3@0";
         let px1 = infix_to_postfix(e1).unwrap();
         assert_eq!(
-            px1.iter().collect::<Vec<_>>(),
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
             vec!["10@0", "2@0", "|", "3@0", "|"]
         );
     }
@@ -153,7 +591,7 @@ mod tests {
         let e1 = "!10@0 | !2@0 | !3@0";
         let px1 = infix_to_postfix(e1).unwrap();
         assert_eq!(
-            px1.iter().collect::<Vec<_>>(),
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
             vec!["10@0", "!", "2@0", "!", "|", "3@0", "!", "|"]
         );
     }
@@ -163,7 +601,7 @@ mod tests {
         let e1 = "(10@0 & !2@0) | (!3@0 & 4@2)";
         let px1 = infix_to_postfix(e1).unwrap();
         assert_eq!(
-            px1.iter().collect::<Vec<_>>(),
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
             vec!["10@0", "2@0", "!", "&", "3@0", "!", "4@2", "&", "|"]
         );
     }
@@ -172,13 +610,20 @@ mod tests {
     fn test_infix_to_postfix_e() {
         let e1 = "10@0 ^ 10@9";
         let px1 = infix_to_postfix(e1).unwrap();
-        assert_eq!(px1.iter().collect::<Vec<_>>(), vec!["10@0", "10@9", "^"]);
+        assert_eq!(
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
+            vec!["10@0", "10@9", "^"]
+        );
     }
 
     #[test]
     fn test_infix_to_postfix_f() {
-        let e1 = "10@0 ^ -10@9";
-        assert!(infix_to_postfix(e1).is_err());
+        let e1 = "10@0 ^ 3@0 - 10@9 & 2@0";
+        let px1 = infix_to_postfix(e1).unwrap();
+        assert_eq!(
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
+            vec!["10@0", "3@0", "10@9", "-", "2@0", "&", "^"]
+        );
     }
 
     #[test]
@@ -186,4 +631,172 @@ mod tests {
         let e1 = "10@0 + 10@9";
         assert!(infix_to_postfix(e1).is_err());
     }
+
+    #[test]
+    fn test_infix_to_postfix_h() {
+        let e1 = "(10@0 | 10@9";
+        assert_eq!(
+            infix_to_postfix(e1).unwrap_err(),
+            SieveError::UnbalancedParenthesis {
+                parenthesis: '(',
+                span: Span::new(0, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_infix_to_postfix_i() {
+        let e1 = "10@0 | 10@9)";
+        assert_eq!(
+            infix_to_postfix(e1).unwrap_err(),
+            SieveError::UnbalancedParenthesis {
+                parenthesis: ')',
+                span: Span::new(11, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_infix_to_postfix_j() {
+        let e1 = "10@0 (3@1)";
+        let px1 = infix_to_postfix(e1).unwrap();
+        assert_eq!(
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
+            vec!["10@0", "3@1"]
+        );
+    }
+
+    #[test]
+    fn test_infix_to_postfix_k() {
+        let e1 = "10@0 % 3@1";
+        assert_eq!(
+            infix_to_postfix(e1).unwrap_err(),
+            SieveError::UnsupportedCharacter {
+                character: '%',
+                span: Span::new(5, 1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_infix_to_postfix_l() {
+        let e1 = "!3@1 & (10@0 | 200@10)";
+        let px1 = infix_to_postfix(e1).unwrap();
+        assert_eq!(
+            px1.iter().map(|t| t.span).collect::<Vec<_>>(),
+            vec![
+                Span::new(1, 3),
+                Span::new(0, 1),
+                Span::new(8, 4),
+                Span::new(15, 6),
+                Span::new(13, 1),
+                Span::new(5, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_infix_to_postfix_n() {
+        let e1 = "3@-1 - 4@-2-5@0";
+        let px1 = infix_to_postfix(e1).unwrap();
+        assert_eq!(
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
+            vec!["3@-1", "4@-2", "-", "5@0", "-"]
+        );
+        assert_eq!(px1[1].span, Span::new(7, 4));
+    }
+
+    #[test]
+    fn test_infix_to_postfix_o() {
+        // whitespace within an operand is ignored
+        let e1 = "3 @ 0 | 4@ -1";
+        let px1 = infix_to_postfix(e1).unwrap();
+        assert_eq!(
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
+            vec!["3@0", "4@-1", "|"]
+        );
+        assert_eq!(px1[0].span, Span::new(0, 5));
+        assert_eq!(px1[1].span, Span::new(8, 5));
+    }
+
+    #[test]
+    fn test_unwrap_display_a() {
+        assert_eq!(unwrap_display("3@1|4@0"), (0, 7));
+        assert_eq!(unwrap_display("Sieve{3@1|4@0}"), (6, 13));
+        assert_eq!(unwrap_display(" Sieve{3@1} "), (7, 10));
+        assert_eq!(unwrap_display("Sieve{3@1"), (0, 9));
+    }
+
+    #[test]
+    fn test_infix_to_postfix_m() {
+        let e1 = "Sieve{(3@1|4@0)&5@0}";
+        let px1 = infix_to_postfix(e1).unwrap();
+        assert_eq!(
+            px1.iter().map(|t| t.value.as_str()).collect::<Vec<_>>(),
+            vec!["3@1", "4@0", "|", "5@0", "&"]
+        );
+        assert_eq!(px1[0].span, Span::new(7, 3));
+    }
+
+    /// Return the tokens of `const_postfix` in the string form of the tokens of `infix_to_postfix`.
+    macro_rules! const_postfix_values {
+        ($expr:expr) => {{
+            const POST: [ConstToken; const_postfix_len($expr)] = const_postfix($expr);
+            POST.iter()
+                .map(|t| match *t {
+                    ConstToken::Residual(m, s) => format!("{m}@{s}"),
+                    ConstToken::Operator(op) => (op as char).to_string(),
+                })
+                .collect::<Vec<_>>()
+        }};
+    }
+
+    #[test]
+    fn test_const_postfix_a() {
+        // the compile-time parser agrees with the runtime parser
+        macro_rules! check {
+            ($($expr:expr),*) => {
+                $(
+                    assert_eq!(
+                        const_postfix_values!($expr),
+                        infix_to_postfix($expr)
+                            .unwrap()
+                            .iter()
+                            .map(|t| t.value.clone())
+                            .collect::<Vec<_>>()
+                    );
+                )*
+            };
+        }
+        check!(
+            "!3@1 & 6@2 | !(10@0 | 2@0 | 3@0 )",
+            "10@0 | 2@0 | 3@0",
+            "!10@0 | !2@0 | !3@0",
+            "(3@1 | 4@0) & 5@0",
+            "3@0 ^ 4@1 & !(5@2 - (6@3|7@4)) | 8@5",
+            "((((3@0))))",
+            "!!!3@0 - 4@0 - 5@0",
+            "Sieve{3@1|4@0}"
+        );
+    }
+
+    #[test]
+    fn test_const_postfix_b() {
+        assert_eq!(
+            const_postfix_values!("3@-1 - 4@-2-5@0"),
+            vec!["3@2", "4@2", "-", "5@0", "-"]
+        );
+        assert_eq!(
+            const_postfix_values!("5@123456789012345678901234567|0@3|7@9"),
+            vec!["5@2", "0@0", "|", "7@2", "|"]
+        );
+        assert_eq!(
+            const_postfix_values!("3 @ 0 | 4@ - 1"),
+            vec!["3@0", "4@3", "|"]
+        );
+        assert_eq!(const_postfix_len("3@0 | (4@1&!5@-2)"), 6);
+        assert_eq!(const_postfix_len(""), 0);
+        assert_eq!(const_postfix_len(" Sieve{3@1|4@0} "), 3);
+        assert_eq!(const_postfix_len("Sieve{"), 1);
+    }
 }
@@ -0,0 +1,507 @@
+use std::ops::Range;
+
+use crate::CompiledSieve;
+use crate::SieveError;
+use crate::SieveNode;
+
+/// The rows drawn when rendering a Sieve; the combined Sieve is always drawn in the last row.
+///
+/// * `Sieve` - Draw only the combined Sieve.
+/// * `Residuals` - Draw a row for each distinct Residual of the expression, in order of appearance.
+/// * `Subtrees` - Draw a row for each distinct subtree of the expression, with operands before the operations that combine them.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderRows {
+    Sieve,
+    Residuals,
+    Subtrees,
+}
+
+/// Options for rendering a Sieve as text. The default options draw a row for each Residual with the characters `█` and `·`, without an axis.
+///
+/// # Fields
+/// * `rows` - The rows to draw.
+/// * `filled` - The character drawn for integers contained in a row.
+/// * `empty` - The character drawn for integers not contained in a row.
+/// * `ticks` - The interval between ticks of an axis drawn below the rows, or `None` for no axis.
+///
+/// ```
+/// use xensieve::{RenderOptions, RenderRows, Sieve};
+/// let options = RenderOptions {
+///     rows: RenderRows::Sieve,
+///     ticks: Some(5),
+///     ..RenderOptions::ascii()
+/// };
+/// assert_eq!(
+///     Sieve::new("5@0|4@2").render_ascii_with(0..12, &options),
+///     "5@0|4@2  #.#..##...#.\n         +----+----+-\n         0    5    10"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub rows: RenderRows,
+    pub filled: char,
+    pub empty: char,
+    pub ticks: Option<u64>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            rows: RenderRows::Residuals,
+            filled: '█',
+            empty: '·',
+            ticks: None,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Return the default options, drawn with the ASCII characters `#` and `.`.
+    pub fn ascii() -> Self {
+        Self {
+            filled: '#',
+            empty: '.',
+            ..Self::default()
+        }
+    }
+}
+
+/// Options for rendering a Sieve as an SVG diagram, with a lane of cells for each row. The default options draw a lane for each Residual with 12-pixel cells, labelled, without an axis.
+///
+/// # Fields
+/// * `rows` - The lanes to draw.
+/// * `cell` - The width and height of each cell, in pixels.
+/// * `filled` - The fill colour of cells of integers contained in a lane.
+/// * `empty` - The fill colour of cells of integers not contained in a lane.
+/// * `stroke` - The colour of the outline of each cell.
+/// * `text` - The colour of labels and axis text.
+/// * `labels` - If `true`, each lane is preceded by the expression it draws.
+/// * `ticks` - The interval between labelled ticks of an axis drawn below the lanes, or `None` for no axis.
+///
+/// ```
+/// use xensieve::{RenderRows, Sieve, SvgOptions};
+/// let options = SvgOptions {
+///     rows: RenderRows::Subtrees,
+///     filled: String::from("black"),
+///     ticks: Some(10),
+///     ..SvgOptions::default()
+/// };
+/// let svg = Sieve::new("(5@0|4@2)&!30@10").to_svg(0..30, &options);
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+/// assert!(svg.contains("(5@0|4@2)&amp;!(30@10)"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SvgOptions {
+    pub rows: RenderRows,
+    pub cell: u32,
+    pub filled: String,
+    pub empty: String,
+    pub stroke: String,
+    pub text: String,
+    pub labels: bool,
+    pub ticks: Option<u64>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            rows: RenderRows::Residuals,
+            cell: 12,
+            filled: String::from("#333333"),
+            empty: String::from("#eeeeee"),
+            stroke: String::from("#ffffff"),
+            text: String::from("#333333"),
+            labels: true,
+            ticks: None,
+        }
+    }
+}
+
+/// Collect the labels and nodes of the rows below `node`, skipping nodes with a label already collected.
+fn collect_rows<'a>(
+    node: &'a SieveNode,
+    rows: RenderRows,
+    post: &mut Vec<(String, &'a SieveNode)>,
+) {
+    let include = match node {
+        SieveNode::Unit(_) => rows != RenderRows::Sieve,
+        SieveNode::Intersection(lhs, rhs)
+        | SieveNode::Union(lhs, rhs)
+        | SieveNode::SymmetricDifference(lhs, rhs)
+        | SieveNode::Difference(lhs, rhs) => {
+            collect_rows(lhs, rows, post);
+            collect_rows(rhs, rows, post);
+            rows == RenderRows::Subtrees
+        }
+        SieveNode::Inversion(part) => {
+            collect_rows(part, rows, post);
+            rows == RenderRows::Subtrees
+        }
+    };
+    let label = node.to_string();
+    if include && post.iter().all(|(l, _)| *l != label) {
+        post.push((label, node));
+    }
+}
+
+/// Return the lines of an axis for `range`, with a tick every `ticks` integers and a label for each tick that does not overlap the label before it.
+fn axis(range: &Range<i128>, ticks: u64) -> (String, String) {
+    let mut marks = String::new();
+    let mut labels = String::new();
+    // the first column at which a label can be placed
+    let mut next = 0;
+    for (i, v) in range.clone().enumerate() {
+        if v.rem_euclid(ticks.max(1) as i128) == 0 {
+            marks.push('+');
+            if i >= next {
+                let label = v.to_string();
+                labels.push_str(&" ".repeat(i - labels.len()));
+                labels.push_str(&label);
+                next = i + label.len() + 1;
+            }
+        } else {
+            marks.push('-');
+        }
+    }
+    (marks, labels)
+}
+
+/// Return the labels and nodes of the rows selected by `rows`, followed by the root if not already included.
+fn select_rows(root: &SieveNode, rows: RenderRows) -> Vec<(String, &SieveNode)> {
+    let mut post: Vec<(String, &SieveNode)> = Vec::new();
+    collect_rows(root, rows, &mut post);
+    let label = root.to_string();
+    if post.iter().all(|(l, _)| *l != label) {
+        post.push((label, root));
+    }
+    post
+}
+
+/// Render the node over the range as lines of text, with a labelled row for each node selected by the options.
+pub(crate) fn render(root: &SieveNode, range: Range<i128>, options: &RenderOptions) -> String {
+    let rows = select_rows(root, options.rows);
+    let width = rows
+        .iter()
+        .map(|(l, _)| l.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<String> = Vec::new();
+    for (label, node) in &rows {
+        let strip: String = range
+            .clone()
+            .map(|v| {
+                if node.contains(v) {
+                    options.filled
+                } else {
+                    options.empty
+                }
+            })
+            .collect();
+        lines.push(format!("{label:<width$}  {strip}"));
+    }
+    if let Some(ticks) = options.ticks {
+        let (marks, labels) = axis(&range, ticks);
+        let indent = " ".repeat(width + 2);
+        lines.push(format!("{indent}{marks}"));
+        lines.push(format!("{indent}{labels}").trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+/// Escape the characters of `value` that cannot appear in SVG text or attribute values.
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render the node over the range as an SVG diagram, with a lane of cells for each node selected by the options. Labels are drawn in a monospace font, sized from the cell.
+pub(crate) fn render_svg(root: &SieveNode, range: Range<i128>, options: &SvgOptions) -> String {
+    let rows = select_rows(root, options.rows);
+    let cell = u64::from(options.cell.max(1));
+    let font = (cell * 3 / 4).max(1);
+    // monospace characters are about six tenths as wide as they are tall
+    let label_width = if options.labels {
+        let chars = rows
+            .iter()
+            .map(|(l, _)| l.chars().count())
+            .max()
+            .unwrap_or(0) as u64;
+        (chars * font * 6 + 9) / 10 + cell / 2
+    } else {
+        0
+    };
+    let gap = cell / 2;
+    let lanes = rows.len() as u64;
+    let count = range.clone().count() as u64;
+    let width = label_width + count * cell;
+    let mut height = lanes * cell + lanes.saturating_sub(1) * gap;
+    if options.ticks.is_some() {
+        height += gap + cell;
+    }
+
+    let mut post = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+    );
+    let text = |x: u64, y: u64, anchor: &str, value: &str| {
+        format!(
+            "<text x=\"{x}\" y=\"{y}\" font-family=\"monospace\" font-size=\"{font}\" fill=\"{}\" text-anchor=\"{anchor}\">{}</text>\n",
+            escape_xml(&options.text),
+            escape_xml(value)
+        )
+    };
+    for (i, (label, node)) in rows.iter().enumerate() {
+        let y = i as u64 * (cell + gap);
+        if options.labels {
+            post.push_str(&text(0, y + font, "start", label));
+        }
+        for (j, v) in range.clone().enumerate() {
+            let fill = if node.contains(v) {
+                &options.filled
+            } else {
+                &options.empty
+            };
+            post.push_str(&format!(
+                "<rect x=\"{}\" y=\"{y}\" width=\"{cell}\" height=\"{cell}\" fill=\"{}\" stroke=\"{}\"/>\n",
+                label_width + j as u64 * cell,
+                escape_xml(fill),
+                escape_xml(&options.stroke)
+            ));
+        }
+    }
+    if let Some(ticks) = options.ticks {
+        let y = height - cell + font;
+        for (j, v) in range.clone().enumerate() {
+            if v.rem_euclid(ticks.max(1) as i128) == 0 {
+                let x = label_width + j as u64 * cell + cell / 2;
+                post.push_str(&text(x, y, "middle", &v.to_string()));
+            }
+        }
+    }
+    post.push_str("</svg>\n");
+    post
+}
+
+/// Return the DOT label of a node: the Residual of a leaf, or the operator of an internal node.
+fn dot_label(node: &SieveNode) -> String {
+    match node {
+        SieveNode::Unit(residual) => residual.to_string(),
+        SieveNode::Intersection(..) => String::from("&"),
+        SieveNode::Union(..) => String::from("|"),
+        SieveNode::SymmetricDifference(..) => String::from("^"),
+        SieveNode::Difference(..) => String::from("-"),
+        SieveNode::Inversion(..) => String::from("!"),
+    }
+}
+
+/// Append the DOT statements of `node` and its children to `lines`, numbering nodes in pre-order from `next`. Returns the number of `node`.
+fn collect_dot(
+    node: &SieveNode,
+    annotate: bool,
+    lines: &mut Vec<String>,
+    next: &mut usize,
+) -> Result<usize, SieveError> {
+    let id = *next;
+    *next += 1;
+    let mut label = dot_label(node);
+    if annotate {
+        let compiled = CompiledSieve::from_node(node, CompiledSieve::DEFAULT_BUDGET)?;
+        let period = compiled.period();
+        let members = compiled.count_in(0..i128::from(period));
+        label.push_str(&format!(
+            "\\nperiod: {period}\\ndensity: {:.6}",
+            members as f64 / period as f64
+        ));
+    }
+    let (shape, children): (&str, Vec<&SieveNode>) = match node {
+        SieveNode::Unit(_) => ("box", vec![]),
+        SieveNode::Intersection(lhs, rhs)
+        | SieveNode::Union(lhs, rhs)
+        | SieveNode::SymmetricDifference(lhs, rhs)
+        | SieveNode::Difference(lhs, rhs) => ("ellipse", vec![lhs, rhs]),
+        SieveNode::Inversion(part) => ("ellipse", vec![part]),
+    };
+    lines.push(format!("    n{id} [label=\"{label}\", shape={shape}];"));
+    for child in children {
+        let c = collect_dot(child, annotate, lines, next)?;
+        lines.push(format!("    n{id} -> n{c};"));
+    }
+    Ok(id)
+}
+
+/// Render the tree of the node as a Graphviz DOT digraph, with operators as internal nodes and Residuals as leaves. If `annotate` is `true`, each node is labelled with the period and density of its subtree, and a `SieveError::ExceedsBudget` is returned if a period exceeds that permitted by `compile`.
+pub(crate) fn render_dot(root: &SieveNode, annotate: bool) -> Result<String, SieveError> {
+    let mut lines: Vec<String> = vec![
+        String::from("digraph Sieve {"),
+        String::from("    node [fontname=\"monospace\"];"),
+    ];
+    collect_dot(root, annotate, &mut lines, &mut 0)?;
+    lines.push(String::from("}"));
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sieve;
+
+    #[test]
+    fn test_render_a() {
+        let s = Sieve::new("(5@0|4@2)&!30@10");
+        let rows = [
+            ("5@0", "█····█····█····█····█···"),
+            ("4@2", "··█···█···█···█···█···█·"),
+            ("30@10", "··········█·············"),
+            ("(5@0|4@2)&!(30@10)", "█·█··██·······██··█·█·█·"),
+        ];
+        assert_eq!(
+            render(&s.root, 0..24, &RenderOptions::default()),
+            rows.iter()
+                .map(|(label, strip)| format!("{label:<18}  {strip}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_b() {
+        let s = Sieve::new("3@0|!(4@1^3@0)");
+        let options = RenderOptions {
+            rows: RenderRows::Subtrees,
+            ..RenderOptions::ascii()
+        };
+        assert_eq!(
+            render(&s.root, -2..6, &options),
+            [
+                "3@0             ..#..#..",
+                "4@1             ...#...#",
+                "4@1^3@0         ..##.#.#",
+                "!(4@1^3@0)      ##..#.#.",
+                "3@0|!(4@1^3@0)  ###.###.",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_c() {
+        // a single Residual is drawn once
+        let s = Sieve::new("3@1");
+        let options = RenderOptions {
+            ticks: Some(4),
+            ..RenderOptions::ascii()
+        };
+        assert_eq!(
+            render(&s.root, -5..7, &options),
+            "3@1  #..#..#..#..\n     -+---+---+--\n      -4  0   4"
+        );
+    }
+
+    #[test]
+    fn test_axis_a() {
+        // overlapping labels are skipped
+        assert_eq!(
+            axis(&(98..106), 2),
+            (String::from("+-+-+-+-"), String::from("98  102"))
+        );
+        assert_eq!(axis(&(0..0), 2), (String::new(), String::new()));
+    }
+
+    #[test]
+    fn test_render_svg_a() {
+        let s = Sieve::new("2@1");
+        let options = SvgOptions {
+            cell: 10,
+            ..SvgOptions::default()
+        };
+        assert_eq!(
+            render_svg(&s.root, 0..2, &options),
+            [
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"38\" height=\"10\" viewBox=\"0 0 38 10\">",
+                "<text x=\"0\" y=\"7\" font-family=\"monospace\" font-size=\"7\" fill=\"#333333\" text-anchor=\"start\">2@1</text>",
+                "<rect x=\"18\" y=\"0\" width=\"10\" height=\"10\" fill=\"#eeeeee\" stroke=\"#ffffff\"/>",
+                "<rect x=\"28\" y=\"0\" width=\"10\" height=\"10\" fill=\"#333333\" stroke=\"#ffffff\"/>",
+                "</svg>",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_svg_b() {
+        let s = Sieve::new("(5@0|4@2)&!30@10");
+        let options = SvgOptions {
+            rows: RenderRows::Subtrees,
+            labels: false,
+            ticks: Some(10),
+            ..SvgOptions::default()
+        };
+        let svg = render_svg(&s.root, 0..30, &options);
+        // six lanes of 12-pixel cells separated by 6 pixels, and an axis
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"360\" height=\"120\""));
+        assert_eq!(svg.matches("<rect").count(), 6 * 30);
+        assert_eq!(svg.matches("<text").count(), 3);
+        assert!(svg.contains(">20</text>"));
+        assert_eq!(
+            svg.matches("fill=\"#333333\" stroke").count(),
+            6 + 7 + 1 + 12 + 29 + 11
+        );
+    }
+
+    #[test]
+    fn test_escape_xml_a() {
+        assert_eq!(escape_xml("3@0&!(4@1)<\">"), "3@0&amp;!(4@1)&lt;&quot;&gt;");
+    }
+
+    #[test]
+    fn test_render_dot_a() {
+        let s = Sieve::new("(5@0|4@2)&!30@10");
+        assert_eq!(
+            render_dot(&s.root, false).unwrap(),
+            [
+                "digraph Sieve {",
+                "    node [fontname=\"monospace\"];",
+                "    n0 [label=\"&\", shape=ellipse];",
+                "    n1 [label=\"|\", shape=ellipse];",
+                "    n2 [label=\"5@0\", shape=box];",
+                "    n1 -> n2;",
+                "    n3 [label=\"4@2\", shape=box];",
+                "    n1 -> n3;",
+                "    n0 -> n1;",
+                "    n4 [label=\"!\", shape=ellipse];",
+                "    n5 [label=\"30@10\", shape=box];",
+                "    n4 -> n5;",
+                "    n0 -> n4;",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_dot_b() {
+        let s = Sieve::new("3@0-6@0");
+        assert_eq!(
+            render_dot(&s.root, true).unwrap(),
+            [
+                "digraph Sieve {",
+                "    node [fontname=\"monospace\"];",
+                "    n0 [label=\"-\\nperiod: 6\\ndensity: 0.166667\", shape=ellipse];",
+                "    n1 [label=\"3@0\\nperiod: 3\\ndensity: 0.333333\", shape=box];",
+                "    n0 -> n1;",
+                "    n2 [label=\"6@0\\nperiod: 6\\ndensity: 0.166667\", shape=box];",
+                "    n0 -> n2;",
+                "}",
+            ]
+            .join("\n")
+        );
+        let s = Sieve::new("1000003@0|1000033@0");
+        assert!(render_dot(&s.root, true).is_err());
+    }
+}
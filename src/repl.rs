@@ -0,0 +1,315 @@
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::ops::Range;
+
+use xensieve::Sieve;
+use xensieve::SieveEnv;
+
+use crate::parse_range;
+
+const HELP: &str = "\
+Enter a Sieve expression to evaluate it, or bind it to a name with `name = expression`.
+Bound names can be combined with the operators `!`, `&`, `-`, `^`, and `|`.
+
+Commands, applied to the given expression or else to the last evaluated Sieve:
+  :values [EXPRESSION]      Print the integers within the window that are contained
+  :states [EXPRESSION]      Print the Boolean state of each integer within the window
+  :intervals [EXPRESSION]   Print the intervals between contained integers within the window
+  :period [EXPRESSION]      Print the period
+  :normal [EXPRESSION]      Print the union of disjoint Residuals
+  :strip [EXPRESSION]       Draw a row for each Residual and a row for the Sieve over the window
+
+Session commands:
+  :window [START..END]      Print or set the window of integers; defaults to 0..24
+  :names                    Print the bound names
+  :save PATH                Save the window and bound names to a file
+  :load PATH                Load a file saved with :save
+  :help                     Print this message
+  :quit                     Exit";
+
+/// Return the Sieve expression of `sieve`, without the `Sieve{...}` notation used by `Display`.
+fn to_expression(sieve: &Sieve) -> String {
+    let post = sieve.to_string();
+    post["Sieve{".len()..post.len() - 1].to_string()
+}
+
+/// Return a line of space-separated values.
+fn join<T: ToString, I: Iterator<Item = T>>(values: I) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+/// The state of an interactive session: the bound names, the window of integers shown, and the last evaluated Sieve.
+pub(crate) struct Session {
+    env: SieveEnv,
+    window: Range<i128>,
+    last: Option<Sieve>,
+}
+
+impl Session {
+    pub(crate) fn new(window: Range<i128>) -> Self {
+        Self {
+            env: SieveEnv::new(),
+            window,
+            last: None,
+        }
+    }
+
+    /// Evaluate `expr` without retaining any bindings, or return the last evaluated Sieve if `expr` is blank.
+    fn sieve(&self, expr: &str) -> Result<Sieve, String> {
+        if expr.is_empty() {
+            return self
+                .last
+                .clone()
+                .ok_or_else(|| String::from("No Sieve has been evaluated."));
+        }
+        match self.env.clone().eval(expr) {
+            Ok(Some(sieve)) => Ok(sieve),
+            Ok(None) => Err(String::from("Expected an expression, not a binding.")),
+            Err(e) => Err(e.render(expr)),
+        }
+    }
+
+    /// Handle one line of input, writing results to `out`. Returns `true` if the session should end.
+    pub(crate) fn handle<W: Write>(&mut self, line: &str, out: &mut W) -> Result<bool, String> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(false);
+        }
+        let io_error = |e: io::Error| e.to_string();
+        if !line.starts_with(':') {
+            // a binding is retained without output
+            if let Some(sieve) = self.env.eval(line).map_err(|e| e.render(line))? {
+                writeln!(out, "{sieve}").map_err(io_error)?;
+                self.last = Some(sieve);
+            }
+            return Ok(false);
+        }
+        let (command, arg) = match line[1..].split_once(char::is_whitespace) {
+            Some((command, arg)) => (command, arg.trim()),
+            None => (&line[1..], ""),
+        };
+        match command {
+            "values" => {
+                let sieve = self.sieve(arg)?;
+                let values = sieve
+                    .values_in(self.window.clone())
+                    .map_err(|e| e.to_string())?;
+                writeln!(out, "{}", join(values)).map_err(io_error)?;
+            }
+            "states" => {
+                let sieve = self.sieve(arg)?;
+                let states = sieve.iter_state(self.window.clone());
+                writeln!(out, "{}", join(states)).map_err(io_error)?;
+            }
+            "intervals" => {
+                let sieve = self.sieve(arg)?;
+                let intervals = sieve.iter_interval(self.window.clone());
+                writeln!(out, "{}", join(intervals)).map_err(io_error)?;
+            }
+            "period" => {
+                let sieve = self.sieve(arg)?;
+                let period = sieve.period().map_err(|e| e.to_string())?;
+                writeln!(out, "{period}").map_err(io_error)?;
+            }
+            "normal" => {
+                let sieve = self.sieve(arg)?;
+                let normal = sieve.to_normal_form().map_err(|e| e.to_string())?;
+                writeln!(out, "{normal}").map_err(io_error)?;
+            }
+            "strip" => {
+                let sieve = self.sieve(arg)?;
+                let strip = sieve.render_ascii(self.window.clone());
+                writeln!(out, "{strip}").map_err(io_error)?;
+            }
+            "window" => {
+                if !arg.is_empty() {
+                    self.window = parse_range(arg)?;
+                }
+                writeln!(out, "{}..{}", self.window.start, self.window.end).map_err(io_error)?;
+            }
+            "names" => {
+                for name in self.env.names() {
+                    let sieve = self.env.get(name).expect("Bound name");
+                    writeln!(out, "{name} = {}", to_expression(sieve)).map_err(io_error)?;
+                }
+            }
+            "save" => {
+                if arg.is_empty() {
+                    return Err(String::from("Missing path."));
+                }
+                fs::write(arg, self.to_file()).map_err(|e| format!("Cannot save {arg}: {e}"))?;
+            }
+            "load" => {
+                if arg.is_empty() {
+                    return Err(String::from("Missing path."));
+                }
+                let content =
+                    fs::read_to_string(arg).map_err(|e| format!("Cannot load {arg}: {e}"))?;
+                for (i, line) in content.lines().enumerate() {
+                    // report errors with the line of the file
+                    let quit = self
+                        .handle(line, out)
+                        .map_err(|e| format!("{arg}:{}: {e}", i + 1))?;
+                    if quit {
+                        return Err(format!("{arg}:{}: Cannot load :quit.", i + 1));
+                    }
+                }
+            }
+            "help" => writeln!(out, "{HELP}").map_err(io_error)?,
+            "quit" | "exit" => return Ok(true),
+            _ => {
+                return Err(format!(
+                    "Unknown command: :{command} (enter :help for commands)."
+                ))
+            }
+        }
+        Ok(false)
+    }
+
+    /// Return the window and bound names as lines that can be loaded with `:load`.
+    fn to_file(&self) -> String {
+        let mut post = format!(":window {}..{}\n", self.window.start, self.window.end);
+        for name in self.env.names() {
+            let sieve = self.env.get(name).expect("Bound name");
+            post.push_str(&format!("{name} = {}\n", to_expression(sieve)));
+        }
+        post
+    }
+}
+
+/// Run an interactive session, reading lines from `input` until `:quit` or the end of input. Errors are reported without ending the session.
+pub(crate) fn run<R, W>(mut input: R, out: &mut W, window: Range<i128>) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut session = Session::new(window);
+    writeln!(
+        out,
+        "xensieve {} (enter :help for commands)",
+        env!("CARGO_PKG_VERSION")
+    )?;
+    loop {
+        write!(out, "> ")?;
+        out.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(out)?;
+            break;
+        }
+        match session.handle(&line, out) {
+            Ok(true) => break,
+            Ok(false) => {}
+            Err(e) => writeln!(out, "{e}")?,
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle(session: &mut Session, line: &str) -> Result<String, String> {
+        let mut out: Vec<u8> = Vec::new();
+        session.handle(line, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_session_a() {
+        let mut s = Session::new(0..12);
+        assert_eq!(handle(&mut s, "a = 3@0|4@1").unwrap(), "");
+        assert_eq!(
+            handle(&mut s, "a - 12@0").unwrap(),
+            "Sieve{(3@0|4@1)-12@0}\n"
+        );
+        assert_eq!(handle(&mut s, ":values").unwrap(), "1 3 5 6 9\n");
+        assert_eq!(handle(&mut s, ":values a").unwrap(), "0 1 3 5 6 9\n");
+        assert_eq!(handle(&mut s, ":intervals a").unwrap(), "1 2 2 1 3\n");
+        assert_eq!(
+            handle(&mut s, ":strip a").unwrap(),
+            "3@0      █··█··█··█··\n4@1      ·█···█···█··\n3@0|4@1  ██·█·██··█··\n"
+        );
+        assert_eq!(handle(&mut s, ":period").unwrap(), "12\n");
+        assert_eq!(
+            handle(&mut s, ":normal !3@0 & 2@0").unwrap(),
+            "Sieve{6@2|6@4}\n"
+        );
+    }
+
+    #[test]
+    fn test_session_b() {
+        let mut s = Session::new(0..24);
+        assert_eq!(handle(&mut s, ":window -4..4").unwrap(), "-4..4\n");
+        assert_eq!(
+            handle(&mut s, ":states 2@0").unwrap(),
+            "true false true false true false true false\n"
+        );
+        assert!(handle(&mut s, ":window 4").is_err());
+        assert_eq!(handle(&mut s, ":window").unwrap(), "-4..4\n");
+    }
+
+    #[test]
+    fn test_session_c() {
+        let mut s = Session::new(0..24);
+        assert_eq!(
+            handle(&mut s, ":values").unwrap_err(),
+            "No Sieve has been evaluated."
+        );
+        assert_eq!(
+            handle(&mut s, "a | 3@0").unwrap_err(),
+            "Undefined name: a (at 0).\na | 3@0\n^"
+        );
+        assert_eq!(
+            handle(&mut s, ":values b = 3@0").unwrap_err(),
+            "Expected an expression, not a binding."
+        );
+        assert_eq!(handle(&mut s, ":names").unwrap(), "");
+        assert!(handle(&mut s, ":foo").is_err());
+        assert_eq!(handle(&mut s, "# comment").unwrap(), "");
+        assert!(s.handle(":quit", &mut Vec::new()).unwrap());
+    }
+
+    #[test]
+    fn test_session_d() {
+        let path = std::env::temp_dir().join(format!("xensieve-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut s1 = Session::new(10..20);
+        handle(&mut s1, "major = 12@0|12@2|12@4|12@5|12@7|12@9|12@11").unwrap();
+        handle(&mut s1, "b = !major").unwrap();
+        handle(&mut s1, &format!(":save {path}")).unwrap();
+
+        let mut s2 = Session::new(0..24);
+        handle(&mut s2, &format!(":load {path}")).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(handle(&mut s2, ":window").unwrap(), "10..20\n");
+        assert_eq!(
+            handle(&mut s2, ":names").unwrap(),
+            "b = !(12@0|12@2|12@4|12@5|12@7|12@9|12@11)\nmajor = 12@0|12@2|12@4|12@5|12@7|12@9|12@11\n"
+        );
+        assert_eq!(handle(&mut s2, ":values b").unwrap(), "10 13 15 18\n");
+        assert!(handle(&mut s2, ":load").is_err());
+
+        fs::write(path, "a = 3@0\nb = a + 1\n").unwrap();
+        let e = handle(&mut s2, &format!(":load {path}")).unwrap_err();
+        fs::remove_file(path).unwrap();
+        assert!(e.starts_with(&format!("{path}:2: Found unsupported character: +")));
+    }
+
+    #[test]
+    fn test_run_a() {
+        let mut out: Vec<u8> = Vec::new();
+        run(
+            "a = 5@0\n:values a\n3@@1\n:quit\n:values a\n".as_bytes(),
+            &mut out,
+            0..12,
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.ends_with("> > 0 5 10\n> Cannot parse Residual: 3@@1 (at 0).\n3@@1\n^^^^\n> "));
+    }
+}
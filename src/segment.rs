@@ -0,0 +1,245 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+use crate::util;
+use crate::Residual;
+use crate::SieveError;
+
+/// The preference used when inferring Residuals from a segment of integers.
+///
+/// * `FewestResiduals` - Greedily select the Residual that covers the most uncovered values, approximating the smallest number of Residuals.
+/// * `SmallestModuli` - For each uncovered value, from the lowest, select the Residual with the smallest modulus, following Ariza (2005).
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentPreference {
+    FewestResiduals,
+    SmallestModuli,
+}
+
+/// The members of a segment within a range of integers.
+struct Segment {
+    start: i128,
+    states: Vec<bool>,
+}
+
+impl Segment {
+    /// Return the members of the segment. A `SieveError::SegmentTooLong` is returned if the range contains more than `limit` integers.
+    fn new(values: &[i128], range: &Range<i128>, limit: usize) -> Result<Self, SieveError> {
+        let len = range
+            .end
+            .checked_sub(range.start)
+            .map_or(u128::MAX, |n| n.max(0) as u128);
+        if len > limit as u128 {
+            return Err(SieveError::SegmentTooLong { len, limit });
+        }
+        let mut states = vec![false; len as usize];
+        for &v in values {
+            if !range.contains(&v) {
+                return Err(SieveError::SegmentOutOfRange { value: v });
+            }
+            states[(v - range.start) as usize] = true;
+        }
+        Ok(Self {
+            start: range.start,
+            states,
+        })
+    }
+
+    /// Return the positions, relative to the start of the range, of the values of the range in the Residual class `r` of modulus `m`.
+    fn class(&self, m: usize, r: usize) -> impl Iterator<Item = usize> {
+        let first = (r as i128 - self.start).rem_euclid(m as i128) as usize;
+        (first..self.states.len()).step_by(m)
+    }
+
+    /// Return `true` if all values of the range in the Residual class `r` of modulus `m` are members of the segment.
+    fn is_covered_by(&self, m: usize, r: usize) -> bool {
+        self.class(m, r).all(|i| self.states[i])
+    }
+
+    /// Return the Residual class of modulus `m` that includes the value at position `i`.
+    fn residue(&self, i: usize, m: usize) -> usize {
+        (self.start + i as i128).rem_euclid(m as i128) as usize
+    }
+
+    /// Return the distance between the positions of the first and last members, or `None` if there are no members. A Residual with a greater modulus contains at most one member.
+    fn span(&self) -> Option<usize> {
+        let first = self.states.iter().position(|&b| b)?;
+        let last = self.states.iter().rposition(|&b| b)?;
+        Some(last - first)
+    }
+}
+
+/// Return the smallest modulus, and its class, of a Residual that contains the value at position `i` and only members of the segment. A modulus equal to the length of the range always qualifies.
+fn smallest_class(segment: &Segment, i: usize) -> (usize, usize) {
+    let len = segment.states.len();
+    (1..=len)
+        .map(|m| (m, segment.residue(i, m)))
+        .find(|&(m, r)| segment.is_covered_by(m, r))
+        .unwrap_or((len, segment.residue(i, len)))
+}
+
+fn smallest_moduli(segment: &Segment) -> Vec<(usize, usize)> {
+    let mut covered = vec![false; segment.states.len()];
+    let mut post: Vec<(usize, usize)> = Vec::new();
+    for i in 0..segment.states.len() {
+        if !segment.states[i] || covered[i] {
+            continue;
+        }
+        let (m, r) = smallest_class(segment, i);
+        for j in segment.class(m, r) {
+            covered[j] = true;
+        }
+        post.push((m, r));
+    }
+    post
+}
+
+fn fewest_residuals(segment: &Segment) -> Vec<(usize, usize)> {
+    let len = segment.states.len();
+    // collect the qualifying classes with more than one member that are not a subset of a qualifying class of a smaller modulus; a subset can never cover more values. A class is a subset of a qualifying class if and only if it is a subset of a qualifying class of modulus `m / p`, for some prime factor `p` of `m`. Moduli greater than the span of the members cover at most one member.
+    let span = segment.span().unwrap_or(0);
+    let mut qualifying: Vec<Vec<bool>> = vec![Vec::new(); span + 1];
+    let mut candidates: Vec<(usize, usize)> = Vec::new();
+    for m in 1..=span {
+        let primes = util::prime_factors(m);
+        let mut states = vec![false; m];
+        for r in 0..m {
+            if primes.iter().any(|&p| qualifying[m / p][r % (m / p)]) {
+                states[r] = true;
+            } else if segment.is_covered_by(m, r) {
+                states[r] = true;
+                if segment.class(m, r).nth(1).is_some() {
+                    candidates.push((m, r));
+                }
+            }
+        }
+        qualifying[m] = states;
+    }
+    // as coverage only decreases as members are covered, counts are evaluated lazily; candidates are ordered by coverage, then by modulus and class, such that the first of equal coverage is selected
+    let mut heap: BinaryHeap<(usize, Reverse<usize>)> = candidates
+        .iter()
+        .enumerate()
+        .map(|(k, &(m, r))| (segment.class(m, r).count(), Reverse(k)))
+        .collect();
+    let mut covered = vec![false; len];
+    let mut post: Vec<(usize, usize)> = Vec::new();
+    while let Some((count, Reverse(k))) = heap.pop() {
+        let (m, r) = candidates[k];
+        let current = segment.class(m, r).filter(|&j| !covered[j]).count();
+        if current < count {
+            if current > 1 {
+                heap.push((current, Reverse(k)));
+            }
+            continue;
+        }
+        for j in segment.class(m, r) {
+            covered[j] = true;
+        }
+        post.push((m, r));
+    }
+    // each remaining member is covered by the smallest class that contains it
+    for i in 0..len {
+        if segment.states[i] && !covered[i] {
+            let (m, r) = smallest_class(segment, i);
+            for j in segment.class(m, r) {
+                covered[j] = true;
+            }
+            post.push((m, r));
+        }
+    }
+    post
+}
+
+/// Return Residuals whose union, within the range, contains exactly the values of the segment. A `SieveError::SegmentOutOfRange` is returned if a value is not within the range; a `SieveError::SegmentTooLong` is returned if the range contains more than `limit` integers.
+pub(crate) fn segment_to_residuals(
+    values: &[i128],
+    range: Range<i128>,
+    preference: SegmentPreference,
+    limit: usize,
+) -> Result<Vec<Residual>, SieveError> {
+    let segment = Segment::new(values, &range, limit)?;
+    let classes = match preference {
+        SegmentPreference::FewestResiduals => fewest_residuals(&segment),
+        SegmentPreference::SmallestModuli => smallest_moduli(&segment),
+    };
+    Ok(classes
+        .into_iter()
+        .map(|(m, r)| Residual::new(m as u64, r as u64))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_a() {
+        let s1 = Segment::new(&[2, 4, 5], &(2..6), 100).unwrap();
+        assert_eq!(s1.states, vec![true, false, true, true]);
+        assert_eq!(s1.class(2, 0).collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(s1.class(3, 2).collect::<Vec<_>>(), vec![0, 3]);
+        assert_eq!(s1.residue(3, 3), 2);
+        assert!(s1.is_covered_by(3, 2));
+        assert!(s1.is_covered_by(2, 0));
+        assert!(!s1.is_covered_by(2, 1));
+    }
+
+    #[test]
+    fn test_segment_b() {
+        assert_eq!(
+            Segment::new(&[2, 6], &(2..6), 100).err(),
+            Some(SieveError::SegmentOutOfRange { value: 6 })
+        );
+    }
+
+    #[test]
+    fn test_segment_to_residuals_a() {
+        let post = segment_to_residuals(
+            &[0, 3, 4, 6, 8, 9],
+            0..12,
+            SegmentPreference::SmallestModuli,
+            100,
+        )
+        .unwrap();
+        assert_eq!(post, vec![Residual::new(3, 0), Residual::new(4, 0)]);
+    }
+
+    #[test]
+    fn test_segment_to_residuals_b() {
+        let values = [1, 4, 9, 10];
+        let post =
+            segment_to_residuals(&values, 0..12, SegmentPreference::SmallestModuli, 100).unwrap();
+        assert_eq!(
+            post,
+            vec![
+                Residual::new(8, 1),
+                Residual::new(5, 4),
+                Residual::new(6, 4)
+            ]
+        );
+        let post =
+            segment_to_residuals(&values, 0..12, SegmentPreference::FewestResiduals, 100).unwrap();
+        assert_eq!(post, vec![Residual::new(5, 4), Residual::new(9, 1)]);
+    }
+
+    #[test]
+    fn test_segment_to_residuals_d() {
+        let values = [1, 2, 3, 5, 7, 9, 11];
+        let post =
+            segment_to_residuals(&values, 0..12, SegmentPreference::SmallestModuli, 100).unwrap();
+        assert_eq!(post, vec![Residual::new(2, 1), Residual::new(5, 2)]);
+        let post =
+            segment_to_residuals(&values, 0..12, SegmentPreference::FewestResiduals, 100).unwrap();
+        assert_eq!(post, vec![Residual::new(2, 1), Residual::new(5, 2)]);
+    }
+
+    #[test]
+    fn test_segment_to_residuals_c() {
+        let post =
+            segment_to_residuals(&[], 0..12, SegmentPreference::FewestResiduals, 100).unwrap();
+        assert_eq!(post, vec![]);
+        let post = segment_to_residuals(&[], 0..0, SegmentPreference::SmallestModuli, 100).unwrap();
+        assert_eq!(post, vec![]);
+    }
+}
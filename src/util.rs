@@ -1,5 +1,14 @@
+use crate::SieveError;
+
+#[cfg(feature = "bigint")]
+use num_bigint::{BigInt, BigUint};
+#[cfg(feature = "bigint")]
+use num_integer::Integer;
+#[cfg(feature = "bigint")]
+use num_traits::Zero;
+
 /// Find the greatest common divisor.
-fn gcd<T>(mut n: T, mut m: T, zero: T) -> Result<T, &'static str>
+pub(crate) fn gcd<T>(mut n: T, mut m: T, zero: T) -> Result<T, &'static str>
 where
     T: std::ops::Rem<Output = T> + std::cmp::Ord + Copy,
 {
@@ -15,31 +24,80 @@ where
     Ok(n)
 }
 
-/// This is a brute-force implementation of modular inverse. The Extended Euclidian Algorithm might be a better choice.
-fn meziriac(a: u64, b: u64) -> Result<u64, &'static str> {
-    let mut g: u64 = 1;
-    if b == 1 {
-        g = 1;
-    } else if a == b {
-        g = 0;
-    } else {
-        while g < u64::MAX {
-            if ((g * a) % b) == 1 {
-                break;
+/// Return all divisors of `n` in ascending order.
+pub(crate) fn divisors(n: usize) -> Vec<usize> {
+    let mut lower: Vec<usize> = Vec::new();
+    let mut upper: Vec<usize> = Vec::new();
+    let mut i = 1;
+    while i * i <= n {
+        if n % i == 0 {
+            lower.push(i);
+            if i * i != n {
+                upper.push(n / i);
+            }
+        }
+        i += 1;
+    }
+    lower.extend(upper.iter().rev());
+    lower
+}
+
+/// Return the distinct prime factors of `n` in ascending order.
+pub(crate) fn prime_factors(mut n: usize) -> Vec<usize> {
+    let mut post: Vec<usize> = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n % p == 0 {
+            post.push(p);
+            while n % p == 0 {
+                n /= p;
             }
-            g += 1;
         }
+        p += 1;
+    }
+    if n > 1 {
+        post.push(n);
     }
-    Ok(g)
+    post
+}
+
+/// Find the least common multiple, returning an error if the result exceeds `u64`.
+pub(crate) fn lcm(n: u64, m: u64) -> Result<u64, &'static str> {
+    let d = gcd(n, m, 0)?;
+    (n / d)
+        .checked_mul(m)
+        .ok_or("least common multiple exceeds u64")
 }
 
-/// Core implementation of intersection of two residual classes.
+/// Find the modular inverse of `a` modulo `b` with the Extended Euclidean Algorithm, returning 0 if `a` and `b` are not coprime.
+fn meziriac(a: u64, b: u64) -> Result<u64, &'static str> {
+    if b == 0 {
+        return Err("zero modulus not supported");
+    }
+    if b == 1 {
+        return Ok(1);
+    }
+    // coefficients are bounded by `b`, so i128 cannot overflow
+    let (mut r0, mut r1) = (b as i128, (a % b) as i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    if r0 != 1 {
+        return Ok(0);
+    }
+    Ok(t0.rem_euclid(b as i128) as u64)
+}
+
+/// Core implementation of intersection of two residual classes, solving the pair of congruences with the Chinese Remainder Theorem. Returns `(0, 0)` if there is no intersection, or a `SieveError::Overflow` if the resulting modulus exceeds `u64`.
 pub(crate) fn intersection(
     m1: u64,
     m2: u64,
     mut s1: u64,
     mut s2: u64,
-) -> Result<(u64, u64), &'static str> {
+) -> Result<(u64, u64), SieveError> {
     if m1 == 0 || m2 == 0 {
         // intersection of null and anything is null
         return Ok((0, 0));
@@ -48,29 +106,70 @@ pub(crate) fn intersection(
     s1 %= m1;
     s2 %= m2;
 
-    // use common divisor
-    let d = gcd(m1, m2, 0)?;
+    // use common divisor; d might be 1
+    let d = gcd(m1, m2, 0).map_err(|_e| SieveError::Overflow)?;
+    let span = s2 as i128 - s1 as i128;
+    if span % d as i128 != 0 {
+        return Ok((0, 0)); // no intersection
+    }
+    let m = lcm(m1, m2).map_err(|_e| SieveError::Overflow)?;
     let md1 = m1 / d;
     let md2 = m2 / d;
-    let span: u64 = (s2 as i128 - s1 as i128).abs().try_into().unwrap();
+    // find k such that s1 + m1 * k is congruent to s2 modulo m2; all products are of values less than 2^64 and fit in u128
+    let inv = meziriac(md1, md2).map_err(|_e| SieveError::Overflow)? as u128;
+    let k = (span / d as i128).rem_euclid(md2 as i128) as u128 * inv % md2 as u128;
+    let s = (s1 as u128 + m1 as u128 * k) % m as u128;
+    Ok((m, s as u64))
+}
 
-    if d != 1 && (span % d != 0) {
-        return Ok((0, 0)); // no intersection
+/// Core implementation of intersection of two residual classes of arbitrary precision; as the resulting modulus cannot overflow, no error is returned. Returns `(0, 0)` if there is no intersection.
+#[cfg(feature = "bigint")]
+pub(crate) fn big_intersection(
+    m1: &BigUint,
+    m2: &BigUint,
+    s1: &BigUint,
+    s2: &BigUint,
+) -> (BigUint, BigUint) {
+    if m1.is_zero() || m2.is_zero() {
+        // intersection of null and anything is null
+        return (BigUint::zero(), BigUint::zero());
     }
-    // NOTE: though this case was specified, it seems impossible to replicate
-    // if d != 1 && (span % d == 0) && (s1 != s2) && (md1 == md2) {
-    //     return Ok((d, s1));
-    // }
-
-    // d might be 1
-    let m = md1 * md2 * d;
-    Ok((m, (s1 + (meziriac(md1, md2).unwrap() * span * md1)) % m))
+    let (m1, m2) = (BigInt::from(m1.clone()), BigInt::from(m2.clone()));
+    let (s1, s2) = (
+        BigInt::from(s1 % m1.magnitude()),
+        BigInt::from(s2 % m2.magnitude()),
+    );
+    // d = m1 * x + m2 * y
+    let e = m1.extended_gcd(&m2);
+    let span = &s2 - &s1;
+    if !(&span % &e.gcd).is_zero() {
+        return (BigUint::zero(), BigUint::zero()); // no intersection
+    }
+    let m = m1.lcm(&m2);
+    let s = (s1 + m1 * e.x * (span / e.gcd)).mod_floor(&m);
+    (m.into_parts().1, s.into_parts().1)
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_prime_factors_a() {
+        assert_eq!(prime_factors(1), vec![]);
+        assert_eq!(prime_factors(12), vec![2, 3]);
+        assert_eq!(prime_factors(49), vec![7]);
+        assert_eq!(prime_factors(30), vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn test_divisors_a() {
+        assert_eq!(divisors(1), vec![1]);
+        assert_eq!(divisors(12), vec![1, 2, 3, 4, 6, 12]);
+        assert_eq!(divisors(49), vec![1, 7, 49]);
+    }
+
     #[test]
     fn test_gcd_a() {
         assert_eq!(gcd(14, 15, 0).unwrap(), 1);
@@ -98,6 +197,19 @@ mod tests {
         assert_eq!(gcd(0, 3, 0).is_err(), true);
     }
 
+    #[test]
+    fn test_lcm_a() {
+        assert_eq!(lcm(4, 6).unwrap(), 12);
+        assert_eq!(lcm(1, 9).unwrap(), 9);
+        assert_eq!(lcm(30, 30).unwrap(), 30);
+    }
+
+    #[test]
+    fn test_lcm_b() {
+        assert!(lcm(0, 6).is_err());
+        assert!(lcm(u64::MAX, u64::MAX - 1).is_err());
+    }
+
     #[test]
     fn test_intersection_a() {
         assert_eq!(intersection(0, 0, 2, 3).unwrap(), (0, 0));
@@ -105,7 +217,79 @@ mod tests {
 
     #[test]
     fn test_intersection_b() {
-        assert_eq!(intersection(45, 40, 11, 1).unwrap(), (360, 101));
+        assert_eq!(intersection(45, 40, 11, 1).unwrap(), (360, 281));
+    }
+
+    #[test]
+    fn test_intersection_c() {
+        for (m1, m2) in [(4, 6), (9, 12), (7, 5), (1, 8), (30, 42)] {
+            for s1 in 0..m1 {
+                for s2 in 0..m2 {
+                    let (m, s) = intersection(m1, m2, s1, s2).unwrap();
+                    let expected: Vec<u64> = (0..m1 * m2)
+                        .filter(|v| v % m1 == s1 && v % m2 == s2)
+                        .collect();
+                    if expected.is_empty() {
+                        assert_eq!((m, s), (0, 0));
+                    } else {
+                        assert_eq!(m, lcm(m1, m2).unwrap());
+                        assert_eq!(s, expected[0]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_intersection_d() {
+        let m1 = u64::MAX;
+        let m2 = u64::MAX - 1;
+        assert_eq!(intersection(m1, m2, 3, 5), Err(SieveError::Overflow));
+        assert_eq!(intersection(m1, 2, 1, 0), Err(SieveError::Overflow));
+    }
+
+    #[test]
+    fn test_intersection_e() {
+        // large prime moduli, whose product is less than u64::MAX
+        let m1 = 4294967291;
+        let m2 = 4294967279;
+        let (m, s) = intersection(m1, m2, 12345, 67890).unwrap();
+        assert_eq!(m, m1 * m2);
+        assert_eq!((s % m1, s % m2), (12345, 67890));
+
+        let m3 = 1 << 62;
+        assert_eq!(
+            intersection(m3, m3 / 2, m3 - 1, m3 / 2 - 1).unwrap(),
+            (m3, m3 - 1)
+        );
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_big_intersection_a() {
+        let big = |n: u64| BigUint::from(n);
+        for (m1, m2) in [(4, 6), (9, 12), (7, 5), (1, 8), (30, 42), (0, 3)] {
+            for s1 in 0..m1 {
+                for s2 in 0..m2 {
+                    let (m, s) = intersection(m1, m2, s1, s2).unwrap();
+                    assert_eq!(
+                        big_intersection(&big(m1), &big(m2), &big(s1), &big(s2)),
+                        (big(m), big(s))
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_big_intersection_b() {
+        let m1 = BigUint::from(u64::MAX);
+        let m2 = BigUint::from(u64::MAX - 1);
+        let (m, s) = big_intersection(&m1, &m2, &BigUint::from(3u8), &BigUint::from(5u8));
+        assert_eq!(m, &m1 * &m2);
+        assert_eq!(&s % &m1, BigUint::from(3u8));
+        assert_eq!(&s % &m2, BigUint::from(5u8));
     }
 
     #[test]
@@ -118,4 +302,13 @@ mod tests {
         assert_eq!(meziriac(20, 9).unwrap(), 5);
         assert_eq!(meziriac(101, 13).unwrap(), 4);
     }
+
+    #[test]
+    fn test_meziriac_b() {
+        let b = u64::MAX;
+        let g = meziriac(2, b).unwrap();
+        assert_eq!((g as u128 * 2) % b as u128, 1);
+        assert_eq!(meziriac(6, 9).unwrap(), 0);
+        assert!(meziriac(6, 0).is_err());
+    }
 }
@@ -0,0 +1,199 @@
+use std::io::Write;
+use std::process::Command;
+use std::process::Output;
+use std::process::Stdio;
+
+/// Run the `xensieve` binary with the given arguments and stdin.
+fn xensieve(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_xensieve"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_cli_values_a() {
+    let post = xensieve(&["values", "(5@0|4@2)&!30@10", "--range", "0..30"], "");
+    assert_eq!(post.status.code(), Some(0));
+    assert_eq!(stdout(&post), "0\n2\n5\n6\n14\n15\n18\n20\n22\n25\n26\n");
+}
+
+#[test]
+fn test_cli_values_b() {
+    let post = xensieve(&["values", "5@0", "-r", "-10..10", "--format=json"], "");
+    assert_eq!(stdout(&post), "[-10,-5,0,5]\n");
+    // the default range is one period from zero
+    let post = xensieve(&["values", "3@0|4@1", "-f", "csv"], "");
+    assert_eq!(stdout(&post), "0,1,3,5,6,9\n");
+}
+
+#[test]
+fn test_cli_states_a() {
+    let post = xensieve(&["states", "5@0", "--range", "0..6", "--format", "csv"], "");
+    assert_eq!(stdout(&post), "true,false,false,false,false,true\n");
+}
+
+#[test]
+fn test_cli_intervals_a() {
+    let post = xensieve(&["intervals", "30@10|5@0", "-r", "0..50", "-f", "json"], "");
+    assert_eq!(stdout(&post), "[5,5,5,5,5,5,5,5,5]\n");
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_cli_contains_a() {
+    let post = xensieve(&["contains", "3@0", "9", "-3"], "");
+    assert_eq!(post.status.code(), Some(0));
+    assert_eq!(stdout(&post), "9 true\n-3 true\n");
+
+    let post = xensieve(&["contains", "3@0", "10"], "");
+    assert_eq!(post.status.code(), Some(1));
+    assert_eq!(stdout(&post), "10 false\n");
+}
+
+#[test]
+fn test_cli_contains_b() {
+    let post = xensieve(&["contains", "3@0|4@1", "-f", "json"], "1\n2 3\n");
+    assert_eq!(post.status.code(), Some(1));
+    assert_eq!(
+        stdout(&post),
+        "[{\"value\":1,\"contains\":true},{\"value\":2,\"contains\":false},{\"value\":3,\"contains\":true}]\n"
+    );
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_cli_period_a() {
+    let post = xensieve(&["period", "3@0|4@1|5@2"], "");
+    assert_eq!(stdout(&post), "60\n");
+}
+
+#[test]
+fn test_cli_normalize_a() {
+    let post = xensieve(&["normalize", "!3@0 & 2@0", "-f", "json"], "");
+    assert_eq!(stdout(&post), "\"Sieve{6@2|6@4}\"\n");
+}
+
+#[test]
+fn test_cli_explain_a() {
+    let post = xensieve(&["explain", "3@0|5@1"], "");
+    assert_eq!(
+        stdout(&post),
+        "expression: Sieve{3@0|5@1}\nperiod: 15\nmembers: 7\ndensity: 0.466667\nempty: false\nuniversal: false\nnormal_form: Sieve{3@0|15@1|15@11}\n"
+    );
+    let post = xensieve(&["explain", "3@0|!3@0", "-f", "csv"], "");
+    assert!(stdout(&post).starts_with("name,value\nexpression,Sieve{3@0|!(3@0)}\nperiod,3\n"));
+    assert!(stdout(&post).contains("universal,true\n"));
+}
+
+#[test]
+fn test_cli_render_a() {
+    let post = xensieve(
+        &["render", "(5@0|4@2)&!30@10", "-r", "0..24", "--ticks", "10"],
+        "",
+    );
+    assert_eq!(
+        stdout(&post),
+        [
+            "5@0                 █····█····█····█····█···",
+            "4@2                 ··█···█···█···█···█···█·",
+            "30@10               ··········█·············",
+            "(5@0|4@2)&!(30@10)  █·█··██·······██··█·█·█·",
+            "                    +---------+---------+---",
+            "                    0         10        20",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn test_cli_svg_a() {
+    let post = xensieve(&["svg", "5@0|4@2", "-r", "0..20", "--rows", "sieve"], "");
+    assert_eq!(post.status.code(), Some(0));
+    let out = stdout(&post);
+    assert!(out.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(out.ends_with("</svg>\n"));
+    assert_eq!(out.matches("<rect").count(), 20);
+}
+
+#[test]
+fn test_cli_dot_a() {
+    let post = xensieve(&["dot", "3@0-6@0", "--annotate"], "");
+    assert_eq!(post.status.code(), Some(0));
+    assert_eq!(
+        stdout(&post),
+        [
+            "digraph Sieve {",
+            "    node [fontname=\"monospace\"];",
+            "    n0 [label=\"-\\nperiod: 6\\ndensity: 0.166667\", shape=ellipse];",
+            "    n1 [label=\"3@0\\nperiod: 3\\ndensity: 0.333333\", shape=box];",
+            "    n0 -> n1;",
+            "    n2 [label=\"6@0\\nperiod: 6\\ndensity: 0.166667\", shape=box];",
+            "    n0 -> n2;",
+            "}",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_cli_error_a() {
+    let post = xensieve(&["values", "3@0 + 4@1"], "");
+    assert_eq!(post.status.code(), Some(2));
+    assert_eq!(stdout(&post), "");
+    assert_eq!(
+        stderr(&post),
+        "Found unsupported character: + (at 4).\n3@0 + 4@1\n    ^\n"
+    );
+}
+
+#[test]
+fn test_cli_error_b() {
+    let post = xensieve(&["values"], "");
+    assert_eq!(post.status.code(), Some(2));
+    assert!(stderr(&post).starts_with("Missing Sieve expression.\n\nUsage: xensieve"));
+
+    let post = xensieve(&["--help"], "");
+    assert_eq!(post.status.code(), Some(0));
+    assert!(stdout(&post).starts_with("Usage: xensieve"));
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_cli_repl_a() {
+    let input = "a = 3@0|4@1\n:values a\n:strip 5@1\n:window 0..6\n:states\n:quit\n";
+    let post = xensieve(&["repl", "--range", "0..12"], input);
+    assert_eq!(post.status.code(), Some(0));
+    let out = stdout(&post);
+    assert!(out.starts_with("xensieve "));
+    assert!(out.ends_with(
+        "> > 0 1 3 5 6 9\n> 5@1  ·█····█····█\n> 0..6\n> No Sieve has been evaluated.\n> "
+    ));
+}
@@ -1,4 +1,12 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use xensieve::CompiledSieve;
+use xensieve::Residual;
+use xensieve::SegmentPreference;
 use xensieve::Sieve;
+use xensieve::SieveEnv;
+use xensieve::SieveError;
+use xensieve::Span;
 
 //------------------------------------------------------------------------------
 #[test]
@@ -52,6 +60,648 @@ fn test_sieve_new_c() {
     assert_eq!(s3.contains(30), true);
 }
 
+#[test]
+fn test_sieve_from_str_a() {
+    let s1: Sieve = "3@0|5@1".parse().unwrap();
+    assert_eq!(s1.iter_value(0..7).collect::<Vec<_>>(), vec![0, 1, 3, 6]);
+}
+
+#[test]
+fn test_sieve_from_str_b() {
+    let e1 = "3@0|5@1)".parse::<Sieve>().unwrap_err();
+    assert_eq!(
+        e1,
+        SieveError::UnbalancedParenthesis {
+            parenthesis: ')',
+            span: Span::new(7, 1)
+        }
+    );
+    assert_eq!(e1.to_string(), "Unbalanced parenthesis: ) (at 7).");
+}
+
+#[test]
+fn test_sieve_error_render_a() {
+    let e1 = "!(3@0|5@1|5@4)|9@6&11@".parse::<Sieve>().unwrap_err();
+    assert_eq!(
+        e1.render("!(3@0|5@1|5@4)|9@6&11@"),
+        "Cannot parse Residual: 11@ (at 19).\n!(3@0|5@1|5@4)|9@6&11@\n                   ^^^"
+    );
+}
+
+#[test]
+fn test_sieve_to_string_a() {
+    let s1 = Sieve::new("(3@0|4@0)&5@0");
+    assert_eq!(s1.to_string(), "Sieve{(3@0|4@0)&5@0}");
+    let s2 = Sieve::new(&s1.to_string());
+    assert_eq!(s2.to_string(), s1.to_string());
+    assert_eq!(
+        s2.iter_value(0..60).collect::<Vec<_>>(),
+        s1.iter_value(0..60).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        s2.iter_value(0..60).collect::<Vec<_>>(),
+        vec![0, 15, 20, 30, 40, 45]
+    );
+}
+
+#[test]
+fn test_sieve_to_string_b() {
+    for e in [
+        "3@0|4@1&5@2",
+        "(3@0|4@1)&5@2",
+        "3@0^(4@1^5@2)",
+        "3@0&!(4@1|5@2)^7@3",
+        "(7@0 | 8@1 | 8@6 ) & !(24@7 | 24@17)",
+        "!(10@1 | 10@2) ^ !(10@7 | 10@8)",
+        "((3@0 & 4@1) | (5@2 ^ 6@3)) & (7@4 | !(8@5 & 9@6))",
+    ] {
+        let s1 = Sieve::new(e);
+        let s2 = Sieve::new(&s1.to_string());
+        assert_eq!(s2.to_string(), s1.to_string());
+        assert_eq!(
+            s2.iter_state(-100..1000).collect::<Vec<_>>(),
+            s1.iter_state(-100..1000).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn test_sieve_to_string_c() {
+    let s1 = Sieve::new("3@0") | (Sieve::new("4@1") | Sieve::new("5@2"));
+    assert_eq!(s1.to_string(), "Sieve{3@0|(4@1|5@2)}");
+    let s2 = (Sieve::new("3@0") | Sieve::new("4@1")) & !Sieve::new("5@2");
+    assert_eq!(s2.to_string(), "Sieve{(3@0|4@1)&!(5@2)}");
+    let s3: Sieve = s2.to_string().parse().unwrap();
+    assert_eq!(s3.to_string(), s2.to_string());
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_sieve_pattern_a() {
+    let s1 = Sieve::new("(5@0|4@2)&!30@10");
+    assert_eq!(s1.period().unwrap(), 60);
+    let p1 = s1.pattern().unwrap();
+    assert_eq!(p1.len(), 60);
+    // the pattern repeats at every period, including negative values
+    assert_eq!(s1.iter_state(60..120).collect::<Vec<_>>(), p1);
+    assert_eq!(s1.iter_state(-180..-120).collect::<Vec<_>>(), p1);
+}
+
+#[test]
+fn test_compiled_sieve_a() {
+    for e in [
+        "(5@0|4@2)&!30@10",
+        "!(10@1 | 10@2) ^ !(10@7 | 10@8)",
+        "(7@0 | 8@1 | 8@6 ) & !(24@7 | 24@17)",
+        "7@0 | (!5@2 & !4@3)",
+        "0@0",
+        "!0@0",
+    ] {
+        let s1 = Sieve::new(e);
+        let c1 = s1.compile().unwrap();
+        assert_eq!(
+            c1.iter_value(-200..200).collect::<Vec<_>>(),
+            s1.iter_value(-200..200).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            c1.iter_state(-200..200).collect::<Vec<_>>(),
+            s1.iter_state(-200..200).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            c1.iter_interval((-200..200).rev()).collect::<Vec<_>>(),
+            s1.iter_interval((-200..200).rev()).collect::<Vec<_>>()
+        );
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_sieve_to_normal_form_a() {
+    let s1 = Sieve::new("(5@0|4@2)&!30@10");
+    let n1 = s1.to_normal_form().unwrap();
+    assert_eq!(
+        n1.to_string(),
+        "Sieve{15@0|12@2|30@5|60@6|60@18|60@20|60@22|30@25|60@34|60@42|60@46|60@54|60@58}"
+    );
+    assert_eq!(
+        n1.iter_state(-120..120).collect::<Vec<_>>(),
+        s1.iter_state(-120..120).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_sieve_to_normal_form_b() {
+    // equivalent expressions have the same normal form
+    let n1 = Sieve::new("!(10@1 | 10@2) ^ !(10@7 | 10@8)")
+        .to_normal_form()
+        .unwrap();
+    let n2 = Sieve::new("10@1|10@2|10@7|10@8").to_normal_form().unwrap();
+    assert_eq!(n1.to_string(), n2.to_string());
+    assert_eq!(n1.to_string(), "Sieve{10@1|5@2|10@8}");
+}
+
+#[test]
+fn test_sieve_to_normal_form_c() {
+    assert_eq!(
+        Sieve::new("3@0&3@1").to_normal_form().unwrap().to_string(),
+        "Sieve{0@0}"
+    );
+    assert_eq!(
+        Sieve::new("!(3@0&3@1)")
+            .to_normal_form()
+            .unwrap()
+            .to_string(),
+        "Sieve{1@0}"
+    );
+    assert_eq!(
+        Sieve::new("2@0^4@0").to_normal_form().unwrap().to_string(),
+        "Sieve{4@2}"
+    );
+}
+
+#[test]
+fn test_sieve_to_normal_form_d() {
+    // the normal form is a union of disjoint residuals
+    let n1 = Sieve::new("(7@0 | 8@1 | 8@6 ) & !(24@7 | 24@17)")
+        .to_normal_form()
+        .unwrap();
+    let parts: Vec<Sieve> = n1
+        .to_string()
+        .trim_start_matches("Sieve{")
+        .trim_end_matches('}')
+        .split('|')
+        .map(Sieve::new)
+        .collect();
+    for i in 0..168 {
+        assert!(parts.iter().filter(|p| p.contains(i)).count() <= 1);
+    }
+}
+
+#[test]
+fn test_sieve_to_normal_form_e() {
+    // beyond the budget of the lookup table, the expression is rewritten with Residual intersections
+    let s1 = Sieve::new("1000000007@3&!(2@0|3@1)");
+    let n1 = s1.to_normal_form().unwrap();
+    assert_eq!(n1.to_string(), "Sieve{6000000042@3|6000000042@4000000031}");
+    for v in [
+        3, 1000000010, 2000000017, 3000000024, 4000000031, 5000000038,
+    ] {
+        assert_eq!(n1.contains(v), s1.contains(v));
+    }
+}
+
+#[test]
+fn test_sieve_to_normal_form_f() {
+    let s1 = Sieve::new("1000003@0|1000033@5");
+    let n1 = s1.to_normal_form().unwrap();
+    for v in [
+        0,
+        5,
+        1000003,
+        1000038,
+        1000036000099,
+        1000036000104,
+        7,
+        -1000028,
+    ] {
+        assert_eq!(n1.contains(v), s1.contains(v));
+    }
+    assert_eq!(
+        Sieve::new("1000000007@0|1000000009@1")
+            .to_normal_form()
+            .unwrap_err(),
+        SieveError::ExceedsBudget {
+            period: 1000000016000000063,
+            budget: CompiledSieve::DEFAULT_BUDGET
+        }
+    );
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_sieve_from_segment_a() {
+    // a major scale over two octaves
+    let v1 = vec![0, 2, 4, 5, 7, 9, 11, 12, 14, 16, 17, 19, 21, 23];
+    let s1 = Sieve::from_segment(&v1, 0..24).unwrap();
+    assert_eq!(s1.iter_value(0..24).collect::<Vec<_>>(), v1);
+    let s2 = Sieve::from_segment_with(&v1, 0..24, SegmentPreference::SmallestModuli).unwrap();
+    assert_eq!(s2.iter_value(0..24).collect::<Vec<_>>(), v1);
+}
+
+#[test]
+fn test_sieve_from_segment_b() {
+    // values are sorted and deduplicated, and may be negative
+    let s1 = Sieve::from_segment(&[7, -5, 1, -5, -2, 4], -6..9).unwrap();
+    assert_eq!(
+        s1.iter_value(-6..9).collect::<Vec<_>>(),
+        vec![-5, -2, 1, 4, 7]
+    );
+    assert_eq!(s1.to_string(), "Sieve{3@1}");
+}
+
+#[test]
+fn test_sieve_from_segment_c() {
+    let s1 = Sieve::new("(5@0|4@2)&!30@10");
+    let v1: Vec<i128> = s1.iter_value(0..120).collect();
+    for preference in [
+        SegmentPreference::FewestResiduals,
+        SegmentPreference::SmallestModuli,
+    ] {
+        let s2 = Sieve::from_segment_with(&v1, 0..120, preference).unwrap();
+        assert_eq!(s2.iter_value(0..120).collect::<Vec<_>>(), v1);
+    }
+}
+
+#[test]
+fn test_sieve_from_segment_d() {
+    assert_eq!(
+        Sieve::from_segment(&[3, 12], 0..12).unwrap_err(),
+        SieveError::SegmentOutOfRange { value: 12 }
+    );
+    assert_eq!(
+        Sieve::from_segment(&[], 0..12).unwrap().to_string(),
+        "Sieve{0@0}"
+    );
+}
+
+#[test]
+fn test_sieve_from_segment_e() {
+    // the primes below 1000
+    let v1: Vec<i128> = (2..1000)
+        .filter(|&v| (2..v).take_while(|d| d * d <= v).all(|d| v % d != 0))
+        .collect();
+    let s1 = Sieve::from_segment(&v1, 0..1000).unwrap();
+    assert_eq!(s1.iter_value(0..1000).collect::<Vec<_>>(), v1);
+    let s2 = Sieve::from_segment(&[0], 0..Sieve::MAX_SEGMENT_LEN as i128).unwrap();
+    assert_eq!(s2.to_string(), "Sieve{8192@0}");
+    assert_eq!(
+        Sieve::from_segment(&[0], 0..10_000).unwrap_err(),
+        SieveError::SegmentTooLong {
+            len: 10_000,
+            limit: Sieve::MAX_SEGMENT_LEN
+        }
+    );
+    assert_eq!(
+        Sieve::from_segment(&[0], i128::MIN..i128::MAX).unwrap_err(),
+        SieveError::SegmentTooLong {
+            len: u128::MAX,
+            limit: Sieve::MAX_SEGMENT_LEN
+        }
+    );
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_sieve_values_in_a() {
+    for e in [
+        "(5@0|4@2)&!30@10",
+        "!(10@1 | 10@2) ^ !(10@7 | 10@8)",
+        "7@0 | (!5@2 & !4@3)",
+        "0@0",
+    ] {
+        let s1 = Sieve::new(e);
+        assert_eq!(
+            s1.values_in(-250..250).unwrap().collect::<Vec<_>>(),
+            s1.iter_value(-250..250).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            s1.iter_from(-250)
+                .unwrap()
+                .take_while(|&v| v < 250)
+                .collect::<Vec<_>>(),
+            s1.iter_value(-250..250).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn test_sieve_iter_from_a() {
+    let s1 = Sieve::new("1000@0&!3000@0");
+    let post1: Vec<_> = s1.iter_from(10_000_000_000_000).unwrap().take(4).collect();
+    assert_eq!(
+        post1,
+        vec![
+            10_000_000_000_000,
+            10_000_000_001_000,
+            10_000_000_003_000,
+            10_000_000_004_000
+        ]
+    );
+}
+
+#[test]
+fn test_sieve_iter_from_b() {
+    let s1 = Sieve::new("18446744073709551557@0|18446744073709551533@1");
+    assert_eq!(s1.iter_from(0).unwrap_err(), SieveError::Overflow);
+}
+
+#[test]
+fn test_sieve_iter_from_c() {
+    // beyond the budget of a lookup table, members are found from the disjoint Residuals
+    let s1 = Sieve::new("1000000007@3");
+    assert_eq!(
+        s1.iter_from(0).unwrap().take(3).collect::<Vec<_>>(),
+        vec![3, 1000000010, 2000000017]
+    );
+    let s2 = Sieve::new("1000003@0|1000033@5");
+    let post2: Vec<_> = s2.values_in(-2000040..2000040).unwrap().collect();
+    assert_eq!(
+        post2,
+        vec![-2000006, -1000028, -1000003, 0, 5, 1000003, 1000038, 2000006]
+    );
+    assert_eq!(
+        Sieve::new("1000000007@0|1000000009@0")
+            .iter_from(0)
+            .unwrap_err(),
+        SieveError::ExceedsBudget {
+            period: 1000000016000000063,
+            budget: CompiledSieve::DEFAULT_BUDGET
+        }
+    );
+}
+
+#[test]
+fn test_sieve_cursor_a() {
+    let s1 = Sieve::new("(5@0|4@2)&!30@10");
+    let post1: Vec<_> = s1.iter_value(-120..120).collect();
+    let i = post1.iter().position(|&v| v >= 0).unwrap();
+
+    let mut c1 = s1.cursor(0).unwrap();
+    assert_eq!(
+        c1.by_ref().take(10).collect::<Vec<_>>(),
+        post1[i..i + 10].to_vec()
+    );
+    let post2: Vec<_> = (0..20).map(|_| c1.prev().unwrap()).collect();
+    assert_eq!(
+        post2,
+        post1[i - 10..i + 10]
+            .iter()
+            .rev()
+            .cloned()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_sieve_cursor_b() {
+    // beyond the budget, the cursor steps the disjoint Residuals
+    let s1 = Sieve::new("1000000007@3|1000000007@5|7@0&1000000007@6");
+    let mut c1 = s1.cursor(0).unwrap();
+    assert_eq!(c1.next(), Some(3));
+    assert_eq!(c1.next(), Some(5));
+    assert_eq!(c1.prev(), Some(5));
+    assert_eq!(c1.prev(), Some(3));
+    // -1000000001 is a multiple of 7
+    assert_eq!(c1.prev(), Some(-1000000001));
+    assert_eq!(c1.nth(3), Some(1000000010));
+    assert_eq!(s1.next_after(5).unwrap(), Some(1000000010));
+    assert_eq!(s1.prev_before(3).unwrap(), Some(-1000000001));
+    assert_eq!(s1.nth_from(0, 2).unwrap(), Some(1000000010));
+}
+
+#[test]
+fn test_sieve_next_after_a() {
+    let s1 = Sieve::new("(5@0|4@2)&!30@10");
+    for v in -60..60 {
+        let next = s1.iter_value(v + 1..).next();
+        assert_eq!(s1.next_after(v).unwrap(), next);
+        let prev = s1.iter_value((-200..v).rev()).next();
+        assert_eq!(s1.prev_before(v).unwrap(), prev);
+    }
+}
+
+#[test]
+fn test_sieve_nth_from_a() {
+    let s1 = Sieve::new("7@0|11@3");
+    let post1: Vec<_> = s1.iter_value(0..).take(501).collect();
+    assert_eq!(s1.nth_from(0, 500).unwrap(), Some(post1[500]));
+    assert_eq!(s1.nth_from(post1[500], -500).unwrap(), Some(0));
+}
+
+#[test]
+fn test_sieve_count_in_a() {
+    let s1 = Sieve::new("!(10@1 | 10@2) ^ !(10@7 | 10@8)");
+    assert_eq!(s1.count_in(-100..100).unwrap(), 80);
+    assert_eq!(
+        s1.count_in(-100..100).unwrap(),
+        s1.iter_value(-100..100).count() as u128
+    );
+}
+
+#[test]
+fn test_sieve_count_in_b() {
+    // beyond the budget, counts are summed from the disjoint Residuals
+    let s1 = Sieve::new("1000000007@3");
+    assert_eq!(s1.count_in(0..4).unwrap(), 1);
+    assert_eq!(s1.count_in(4..1000000010).unwrap(), 0);
+    assert_eq!(
+        s1.count_in(i128::MIN..i128::MAX).unwrap(),
+        340282364538961911690641225597
+    );
+    // overlapping Residuals of large coprime moduli cannot be made disjoint within the budget
+    let s2 = Sieve::new("1000000007@3|1000000009@3");
+    assert!(s2.count_in(0..4).is_err());
+    let s3 = Sieve::new("1000000007@3|1000000007@4|2@0&1000000007@5");
+    assert_eq!(s3.count_in(-4000000028..4000000028).unwrap(), 20);
+    assert_eq!(s3.rank(4000000028).unwrap(), 10);
+    assert_eq!(s3.select(10).unwrap(), Some(4000000031));
+    assert_eq!(s3.select(-1).unwrap(), Some(-1000000002));
+}
+
+#[test]
+fn test_sieve_count_in_c() {
+    // every integer is a member
+    let s1 = Sieve::new("3@0|3@1|3@2");
+    assert_eq!(s1.count_in(i128::MIN..0).unwrap(), 1 << 127);
+    assert_eq!(s1.count_in(i128::MIN..i128::MAX).unwrap(), u128::MAX);
+    let s2 = Sieve::new("3@1|3@2");
+    assert_eq!(s2.count_in(i128::MIN..i128::MIN + 3).unwrap(), 2);
+    assert_eq!(s2.count_in(i128::MIN..0).unwrap(), (1 << 127) / 3 * 2 + 2);
+}
+
+#[test]
+fn test_sieve_rank_a() {
+    let s1 = Sieve::new("(5@0|4@2)&!30@10");
+    let post1: Vec<_> = s1.iter_value(0..100).collect();
+    for (k, v) in post1.iter().enumerate() {
+        assert_eq!(s1.rank(*v).unwrap(), k as i128);
+        assert_eq!(s1.select(k as i128).unwrap(), Some(*v));
+    }
+}
+
+#[test]
+fn test_sieve_rank_b() {
+    // every integer is a member
+    let s1 = Sieve::new("3@0|3@1|3@2");
+    assert_eq!(s1.rank(i128::MIN).unwrap(), i128::MIN);
+    assert_eq!(s1.rank(i128::MAX).unwrap(), i128::MAX);
+    assert_eq!(s1.select(i128::MIN).unwrap(), Some(i128::MIN));
+    assert_eq!(s1.prev_before(i128::MIN + 1).unwrap(), Some(i128::MIN));
+    assert_eq!(s1.prev_before(i128::MIN).unwrap(), None);
+    assert_eq!(s1.next_after(i128::MAX - 1).unwrap(), Some(i128::MAX));
+    let s2 = Sieve::new("3@1|3@2");
+    assert_eq!(s2.prev_before(i128::MIN + 1).unwrap(), Some(i128::MIN));
+    assert_eq!(s2.prev_before(i128::MIN).unwrap(), None);
+    assert_eq!(
+        s2.rank(i128::MIN + 1).unwrap() - s2.rank(i128::MIN).unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_sieve_select_a() {
+    assert_eq!(Sieve::new("3@1&3@2").select(0).unwrap(), None);
+    assert_eq!(Sieve::new("3@1&3@2").rank(10).unwrap(), 0);
+}
+
+//------------------------------------------------------------------------------
+
+#[test]
+fn test_residual_a() {
+    let r1 = Residual::new(5, 0);
+    let r2 = Residual::new(4, 2);
+    let s1 = Sieve::from(r1) | Sieve::from_residual(r2);
+    assert_eq!(s1.to_string(), "Sieve{5@0|4@2}");
+    assert_eq!(
+        (s1 & !Sieve::from(Residual::new(30, 10)))
+            .iter_value(0..50)
+            .collect::<Vec<_>>(),
+        Sieve::new("(5@0|4@2)&!30@10")
+            .iter_value(0..50)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_residual_c() {
+    let r1 = Residual::new(u64::MAX, 1);
+    let r2 = Residual::new(u64::MAX - 1, 1);
+    assert_eq!(r1.checked_intersection(&r2), Err(SieveError::Overflow));
+    let s1 = Sieve::from(r1) & Sieve::from(r2);
+    assert_eq!(s1.period(), Err(SieveError::Overflow));
+    assert!(s1.contains(1));
+    assert!(!s1.contains(2));
+}
+
+#[test]
+fn test_residual_b() {
+    let r1 = Residual::new(6, 2);
+    assert_eq!((r1.modulus(), r1.shift()), (6, 2));
+    let mut v = -20;
+    let mut post = Vec::new();
+    while let Some(n) = r1.next_after(v).filter(|&n| n < 20) {
+        post.push(n);
+        v = n;
+    }
+    assert_eq!(
+        post,
+        Sieve::from(r1).iter_value(-19..20).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_sieve_negative_shift_a() {
+    // textbook notation with negative shifts
+    let s1 = Sieve::new("(12@-1 | 12@-5) - 4@-1");
+    assert_eq!(s1.to_string(), "Sieve{(12@11|12@7)-4@3}");
+    assert_eq!(s1.iter_value(0..24).collect::<Vec<_>>(), vec![]);
+    let s2 = Sieve::new("5@-1 | 5@1");
+    assert_eq!(
+        s2.iter_value(-6..6).collect::<Vec<_>>(),
+        vec![-6, -4, -1, 1, 4]
+    );
+    assert_eq!(
+        Sieve::try_new("5@1 -").unwrap_err(),
+        SieveError::MissingOperand {
+            operator: '-',
+            span: Span::new(4, 1)
+        }
+    );
+}
+
+#[test]
+fn test_sieve_bindings_a() {
+    let s1 = Sieve::new("a = 3@0|4@1; b = 5@2; (a & !b) | (b ^ 12@7)");
+    let s2 = Sieve::new("((3@0|4@1) & !5@2) | (5@2 ^ 12@7)");
+    assert_eq!(s1, s2);
+
+    let e1 = Sieve::try_new("a = 3@0; a | c").unwrap_err();
+    assert_eq!(
+        e1.render("a = 3@0; a | c"),
+        "Undefined name: c (at 13).\na = 3@0; a | c\n             ^"
+    );
+
+    let mut env = SieveEnv::new();
+    env.insert("c", Sieve::new("7@0")).unwrap();
+    let s3 = env.eval("a = 3@0; a | c").unwrap().unwrap();
+    assert_eq!(
+        s3.iter_value(0..10).collect::<Vec<_>>(),
+        vec![0, 3, 6, 7, 9]
+    );
+}
+
+#[test]
+fn test_sieve_macro_a() {
+    let s1 = xensieve::sieve!("(12@-1 | 12@-5) - 4@-1 | !(3@0 ^ 5@1)");
+    let s2 = Sieve::new("(12@-1 | 12@-5) - 4@-1 | !(3@0 ^ 5@1)");
+    assert_eq!(s1.to_string(), s2.to_string());
+    assert_eq!(
+        s1.iter_value(-30..30).collect::<Vec<_>>(),
+        s2.iter_value(-30..30).collect::<Vec<_>>()
+    );
+    const EXPR: &str = "Sieve{3@0|4@1}";
+    assert_eq!(xensieve::sieve!(EXPR).to_string(), EXPR);
+}
+
+#[test]
+fn test_sieve_relations_a() {
+    let submitted = [
+        "(5@0|4@2)&!30@10",
+        "4@2|5@0&!30@10",
+        "(4@2 | 5@0) & !(30@10)",
+        "!(!(5@0|4@2)|30@10)",
+    ];
+    let sieves: Vec<Sieve> = submitted.iter().map(|e| Sieve::new(e)).collect();
+    let unique: std::collections::HashSet<&Sieve> = sieves.iter().collect();
+    // the second expression differs by precedence
+    assert_eq!(unique.len(), 2);
+    assert_eq!(sieves[0], sieves[2]);
+    assert_eq!(sieves[0], sieves[3]);
+    assert_ne!(sieves[0], sieves[1]);
+    assert!(sieves[0].is_subset(&sieves[1]).unwrap());
+    assert!(sieves[1].is_superset(&sieves[0]).unwrap());
+}
+
+#[test]
+fn test_sieve_transform_a() {
+    // transposition, augmentation and inversion of a scale
+    let s1 = Sieve::new("(5@0|4@2)&!30@10");
+    let s2 = (-(&s1 * 2) + 3) - 1;
+    let post: Vec<i64> = s2.iter_value_int(-20i64..=0).collect();
+    let expected: Vec<i64> = (-20i64..=0)
+        .filter(|v| {
+            let x = -(v - 2);
+            x % 2 == 0 && s1.contains_int(x / 2)
+        })
+        .collect();
+    assert_eq!(post, expected);
+    assert_eq!(Sieve::new(&s2.to_string()).to_string(), s2.to_string());
+}
+
+#[test]
+fn test_sieve_transform_b() {
+    let s1 = Sieve::new("18446744073709551615@0");
+    assert_eq!(s1.checked_scaled(2).unwrap_err(), SieveError::Overflow);
+    assert_eq!(
+        s1.shifted(-1).to_string(),
+        "Sieve{18446744073709551615@18446744073709551614}"
+    );
+}
+
 //------------------------------------------------------------------------------
 
 #[test]
@@ -165,6 +815,73 @@ fn test_sieve_iter_int_i() {
     assert_eq!(post1, vec![1, 2, 7, 8]);
 }
 
+#[test]
+fn test_sieve_iter_int_j() {
+    let s1 = Sieve::new("3@0|4@1");
+    let post1: Vec<u8> = s1.iter_value_int(0u8..=12).collect();
+    assert_eq!(post1, vec![0, 1, 3, 5, 6, 9, 12]);
+
+    let post2: Vec<i64> = s1.iter_value_int(-6i64..0).collect();
+    assert_eq!(post2, vec![-6, -3]);
+
+    let post3: Vec<usize> = s1.iter_value_int(250usize..260).collect();
+    assert_eq!(post3, vec![252, 253, 255, 257, 258]);
+}
+
+#[test]
+fn test_sieve_iter_int_k() {
+    let s1 = Sieve::new("5@3");
+    let post1: Vec<u128> = s1.iter_value_int((u128::MAX - 10)..=u128::MAX).collect();
+    assert!(post1.iter().all(|v| v % 5 == 3));
+    assert_eq!(post1.len(), 2);
+
+    let post2: Vec<i8> = s1.iter_value_int(i8::MIN..=i8::MAX).collect();
+    assert_eq!(post2.first(), Some(&-127));
+    assert_eq!(post2.last(), Some(&123));
+}
+
+#[test]
+fn test_sieve_iter_int_l() {
+    let s1 = Sieve::new("3@0|4@0");
+    let post1: Vec<u32> = s1.iter_interval_int(0u32..=12).collect();
+    assert_eq!(post1, vec![3, 1, 2, 2, 1, 3]);
+    let post2: Vec<bool> = s1.iter_state_int(0u16..4).collect();
+    assert_eq!(post2, vec![true, false, false, true]);
+
+    let c1 = s1.compile().unwrap();
+    let post3: Vec<i16> = c1.iter_interval_int(-12i16..=0).collect();
+    assert_eq!(post3, vec![3, 1, 2, 2, 1, 3]);
+    assert_eq!(c1.contains_int(u64::MAX), s1.contains_int(u64::MAX));
+}
+
+#[test]
+fn test_sieve_iter_int_m() {
+    // untyped literals beyond `i32` are inferred as `i128`
+    let s1 = Sieve::new("1000@0");
+    assert!(s1.contains(3_000_000_000));
+    assert_eq!(
+        s1.iter_value(2_999_999_000..3_000_000_001)
+            .collect::<Vec<_>>(),
+        vec![2_999_999_000, 3_000_000_000]
+    );
+    assert_eq!(
+        s1.iter_from(10_000_000_000).unwrap().next(),
+        Some(10_000_000_000)
+    );
+}
+
+#[test]
+fn test_sieve_iter_int_n() {
+    // intervals that cannot be represented by the type end iteration
+    let s1 = Sieve::new("200@100|200@101");
+    let post1: Vec<i8> = s1.iter_interval_int(-100i8..=100).collect();
+    assert_eq!(post1, vec![1]);
+    let post2: Vec<u8> = s1.iter_interval_int((0u8..=200).rev()).collect();
+    assert_eq!(post2, vec![]);
+    let post3: Vec<i128> = s1.iter_interval((-100..=101).rev()).collect();
+    assert_eq!(post3, vec![-1, -199, -1]);
+}
+
 //------------------------------------------------------------------------------
 
 #[test]